@@ -0,0 +1,179 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+};
+
+use api_response::error_code::catalog;
+
+/// One error code of a catalog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub code: u64,
+    /// The X/Y/Z segments of the path, e.g. `X00(product)`.
+    pub path: [String; 3],
+    pub messages: BTreeSet<String>,
+}
+
+impl Entry {
+    pub fn path(&self) -> String {
+        self.path.join("/")
+    }
+    /// The path without its flags, which identifies an error across
+    /// renumbering.
+    pub fn path_names(&self) -> [&str; 3] {
+        self.path.each_ref().map(|segment| segment_name(segment))
+    }
+    pub fn message(&self) -> String {
+        self.messages.iter().cloned().collect::<Vec<_>>().join(" | ")
+    }
+}
+
+/// A catalog of error codes, sorted by code.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Catalog {
+    pub entries: BTreeMap<u64, Entry>,
+}
+
+type TextTree = BTreeMap<String, BTreeMap<String, BTreeMap<String, BTreeMap<String, BTreeSet<String>>>>>;
+
+impl Catalog {
+    /// Load a catalog exported by `ErrDeclTally::catalog()`, or the text tree
+    /// exported by `ErrDeclTally::json()`.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|e| format!("invalid catalog: {e}"))?;
+        if value.get("version").is_some_and(serde_json::Value::is_number) {
+            let versioned = catalog::Catalog::from_json(json).map_err(|e| e.to_string())?;
+            return Ok(Self::from_versioned(&versioned));
+        }
+        Self::from_text_tree(value)
+    }
+
+    fn from_versioned(versioned: &catalog::Catalog) -> Self {
+        let mut entries: BTreeMap<u64, Entry> = BTreeMap::new();
+        for entry in &versioned.entries {
+            let [x, y, z] = &entry.path;
+            entries
+                .entry(entry.code)
+                .or_insert_with(|| Entry {
+                    code: entry.code,
+                    path: [
+                        format!("X{:02}({})", x.flag, x.name),
+                        format!("Y{:02}({})", y.flag, y.name),
+                        format!("Z{:02}({})", z.flag, z.name),
+                    ],
+                    messages: BTreeSet::new(),
+                })
+                .messages
+                .insert(entry.type_text.clone());
+        }
+        Self { entries }
+    }
+
+    fn from_text_tree(value: serde_json::Value) -> Result<Self, String> {
+        let tree: TextTree = serde_json::from_value(value).map_err(|e| format!("invalid catalog: {e}"))?;
+        let mut entries = BTreeMap::new();
+        for (x, ys) in tree {
+            for (y, zs) in ys {
+                for (z, codes) in zs {
+                    for (code, messages) in codes {
+                        let code = code
+                            .strip_prefix("ErrCode(")
+                            .and_then(|v| v.strip_suffix(')'))
+                            .and_then(|v| v.parse::<u64>().ok())
+                            .ok_or_else(|| format!("invalid error code key: {code}"))?;
+                        entries.insert(
+                            code,
+                            Entry {
+                                code,
+                                path: [x.clone(), y.clone(), z.clone()],
+                                messages,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+        Ok(Self { entries })
+    }
+
+    /// Render the catalog as a plain text table.
+    pub fn table(&self) -> String {
+        let header = ["CODE", "PATH", "MESSAGE"];
+        let rows: Vec<[String; 3]> = self
+            .entries
+            .values()
+            .map(|entry| [entry.code.to_string(), entry.path(), entry.message()])
+            .collect();
+        let width = |idx: usize| {
+            rows.iter()
+                .map(|row| row[idx].chars().count())
+                .chain([header[idx].len()])
+                .max()
+                .unwrap_or_default()
+        };
+        let (code_width, path_width) = (width(0), width(1));
+        let mut table = String::new();
+        let _ = writeln!(
+            table,
+            "{:code_width$}  {:path_width$}  {}",
+            header[0], header[1], header[2]
+        );
+        for [code, path, message] in rows {
+            let _ = writeln!(table, "{code:code_width$}  {path:path_width$}  {message}");
+        }
+        table
+    }
+}
+
+/// Returns the name of a path segment such as `X00(product)`.
+fn segment_name(segment: &str) -> &str {
+    segment
+        .split_once('(')
+        .and_then(|(_, rest)| rest.strip_suffix(')'))
+        .unwrap_or(segment)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use api_response::error_code::{ErrPathRoot, catalog, ety_grpc};
+
+    use super::Catalog;
+
+    pub(crate) const V1: &str = r#"{
+        "X00(product)": {
+            "Y01(system)": {
+                "Z20(user)": {
+                    "ErrCode(1004000120)": ["Some requested entity was not found."],
+                    "ErrCode(1002000120)": ["Invalid request argument."]
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn load_and_table() {
+        let catalog = Catalog::from_json(V1).unwrap();
+        assert_eq!(2, catalog.entries.len());
+        let entry = &catalog.entries[&1004000120];
+        assert_eq!("X00(product)/Y01(system)/Z20(user)", entry.path());
+        assert_eq!(["product", "system", "user"], entry.path_names());
+        let table = catalog.table();
+        let lines: Vec<_> = table.lines().collect();
+        assert_eq!(3, lines.len());
+        assert!(lines[1].starts_with("1002000120  X00(product)/Y01(system)/Z20(user)  Invalid request argument."));
+        assert!(Catalog::from_json(r#"{"X00(a)":{"Y00(b)":{"Z00(c)":{"Code(1)":[]}}}}"#).is_err());
+    }
+
+    #[test]
+    fn load_versioned() {
+        let ep = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        let err_decls = [ety_grpc::NOT_FOUND.declare(ep), ety_grpc::INVALID_ARGUMENT.declare(ep)];
+        let json = catalog::Catalog::from_err_decls(&err_decls).to_json();
+        assert_eq!(Catalog::from_json(V1).unwrap(), Catalog::from_json(&json).unwrap());
+        assert!(
+            Catalog::from_json(r#"{"version":99,"entries":[]}"#)
+                .unwrap_err()
+                .contains("unsupported catalog version 99")
+        );
+    }
+}
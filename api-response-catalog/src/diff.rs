@@ -0,0 +1,135 @@
+use std::fmt;
+
+use crate::catalog::{Catalog, Entry};
+
+/// A change of one error code between two catalogs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added(Entry),
+    Removed(Entry),
+    /// The same path and message moved to another code.
+    Renumbered {
+        old: Entry,
+        new: Entry,
+    },
+    /// The same code got another message.
+    Remessaged {
+        old: Entry,
+        new: Entry,
+    },
+}
+
+impl Change {
+    /// Clients matching on numeric codes break on removed and renumbered
+    /// codes. A new message only breaks them in strict mode.
+    pub const fn is_breaking(&self, strict: bool) -> bool {
+        match self {
+            Self::Added(_) => false,
+            Self::Removed(_) | Self::Renumbered { .. } => true,
+            Self::Remessaged { .. } => strict,
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Added(entry) => write!(f, "added       {} {} {}", entry.code, entry.path(), entry.message()),
+            Self::Removed(entry) => write!(f, "removed     {} {} {}", entry.code, entry.path(), entry.message()),
+            Self::Renumbered { old, new } => write!(
+                f,
+                "renumbered  {} -> {} {} -> {} {}",
+                old.code,
+                new.code,
+                old.path(),
+                new.path(),
+                new.message()
+            ),
+            Self::Remessaged { old, new } => write!(
+                f,
+                "remessaged  {} {} {:?} -> {:?}",
+                new.code,
+                new.path(),
+                old.message(),
+                new.message()
+            ),
+        }
+    }
+}
+
+/// Compare two catalogs.
+///
+/// A removed code is reported as renumbered when an added code has the same
+/// path names and shares a message with it.
+pub fn diff(old: &Catalog, new: &Catalog) -> Vec<Change> {
+    let mut removed: Vec<&Entry> = vec![];
+    let mut changes = vec![];
+    for (code, old_entry) in &old.entries {
+        match new.entries.get(code) {
+            Some(new_entry) if new_entry.messages != old_entry.messages => changes.push(Change::Remessaged {
+                old: old_entry.clone(),
+                new: new_entry.clone(),
+            }),
+            Some(_) => {}
+            None => removed.push(old_entry),
+        }
+    }
+    let mut added: Vec<&Entry> = new
+        .entries
+        .iter()
+        .filter(|(code, _)| !old.entries.contains_key(code))
+        .map(|(_, entry)| entry)
+        .collect();
+    for old_entry in removed {
+        let renumbered = added.iter().position(|new_entry| {
+            new_entry.path_names() == old_entry.path_names() && !new_entry.messages.is_disjoint(&old_entry.messages)
+        });
+        match renumbered {
+            Some(idx) => changes.push(Change::Renumbered {
+                old: old_entry.clone(),
+                new: added.remove(idx).clone(),
+            }),
+            None => changes.push(Change::Removed(old_entry.clone())),
+        }
+    }
+    changes.extend(added.into_iter().cloned().map(Change::Added));
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, diff};
+    use crate::catalog::{Catalog, tests::V1};
+
+    #[test]
+    fn changes() {
+        let v2 = r#"{
+            "X00(product)": {
+                "Y01(system)": {
+                    "Z21(user)": {
+                        "ErrCode(1004000121)": ["Some requested entity was not found."]
+                    },
+                    "Z20(user)": {
+                        "ErrCode(1002000120)": ["Invalid user argument."],
+                        "ErrCode(1006000120)": ["No permission to execute the request."]
+                    }
+                }
+            }
+        }"#;
+        let old = Catalog::from_json(V1).unwrap();
+        let new = Catalog::from_json(v2).unwrap();
+        let changes = diff(&old, &new);
+        assert_eq!(3, changes.len());
+        assert!(matches!(&changes[0], Change::Remessaged { new, .. } if new.code == 1002000120));
+        assert!(
+            matches!(&changes[1], Change::Renumbered { old, new } if old.code == 1004000120 && new.code == 1004000121)
+        );
+        assert!(matches!(&changes[2], Change::Added(entry) if entry.code == 1006000120));
+        assert_eq!(1, changes.iter().filter(|v| v.is_breaking(false)).count());
+        assert_eq!(2, changes.iter().filter(|v| v.is_breaking(true)).count());
+
+        let removed = diff(&new, &Catalog::default());
+        assert!(removed.iter().all(|v| matches!(v, Change::Removed(_))));
+        assert!(diff(&old, &old).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Dump and diff the error code catalogs exported by
+//! `ErrDeclTally::catalog()` (or the older `ErrDeclTally::json()` text tree).
+//!
+//! ```text
+//! api-response-catalog show <catalog.json>
+//! api-response-catalog diff [--strict] <old.json> <new.json>
+//! api-response-catalog ts [--lite] <catalog.json>
+//! ```
+//!
+//! `diff` exits with status 1 when a code was removed or renumbered (and, with
+//! `--strict`, when a message changed), so it can gate releases.
+
+mod catalog;
+mod diff;
+
+use std::{fs, process::ExitCode};
+
+use api_response::{
+    error_code::catalog::Catalog as VersionedCatalog,
+    typescript::{self, Envelope},
+};
+use catalog::Catalog;
+
+const USAGE: &str = "\
+Usage:
+  api-response-catalog show <catalog.json>
+  api-response-catalog diff [--strict] <old.json> <new.json>
+  api-response-catalog ts [--lite] <catalog.json>";
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match run(&args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("error: {e}\n\n{USAGE}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<ExitCode, String> {
+    match args.first().map(String::as_str) {
+        Some("show") => {
+            let [path] = positional::<1>(&args[1..], &[])?;
+            print!("{}", load(path)?.table());
+            Ok(ExitCode::SUCCESS)
+        }
+        Some("diff") => {
+            let strict = args.iter().any(|v| v == "--strict");
+            let [old, new] = positional::<2>(&args[1..], &["--strict"])?;
+            let changes = diff::diff(&load(old)?, &load(new)?);
+            let mut breaking = false;
+            for change in &changes {
+                let is_breaking = change.is_breaking(strict);
+                breaking |= is_breaking;
+                println!("{}{change}", if is_breaking { "! " } else { "  " });
+            }
+            if breaking {
+                eprintln!("breaking changes found");
+                Ok(ExitCode::FAILURE)
+            } else {
+                Ok(ExitCode::SUCCESS)
+            }
+        }
+        Some("ts") => {
+            let envelope = if args.iter().any(|v| v == "--lite") {
+                Envelope::Lite
+            } else {
+                Envelope::Tagged
+            };
+            let [path] = positional::<1>(&args[1..], &["--lite"])?;
+            let json = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+            let catalog = VersionedCatalog::from_json(&json).map_err(|e| format!("{path}: {e}"))?;
+            print!("{}", typescript::generate(&catalog, envelope));
+            Ok(ExitCode::SUCCESS)
+        }
+        Some("-h" | "--help") => {
+            println!("{USAGE}");
+            Ok(ExitCode::SUCCESS)
+        }
+        Some(other) => Err(format!("unknown command: {other}")),
+        None => Err("missing command".to_owned()),
+    }
+}
+
+/// Returns exactly `N` positional arguments, skipping the `flags` of the
+/// command and rejecting any other option.
+fn positional<'a, const N: usize>(args: &'a [String], flags: &[&str]) -> Result<[&'a str; N], String> {
+    let mut values = Vec::with_capacity(N);
+    for arg in args {
+        if !arg.starts_with('-') {
+            values.push(arg.as_str());
+        } else if !flags.contains(&arg.as_str()) {
+            return Err(format!("unknown option: {arg}"));
+        }
+    }
+    values
+        .try_into()
+        .map_err(|v: Vec<&str>| format!("expected {N} file argument(s), got {}", v.len()))
+}
+
+fn load(path: &str) -> Result<Catalog, String> {
+    let json = fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"))?;
+    Catalog::from_json(&json).map_err(|e| format!("{path}: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::positional;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| (*v).to_owned()).collect()
+    }
+
+    #[test]
+    fn positional_flags() {
+        assert_eq!(
+            Ok(["a", "b"]),
+            positional::<2>(&args(&["--strict", "a", "b"]), &["--strict"])
+        );
+        assert_eq!(
+            Err("unknown option: --strcit".to_owned()),
+            positional::<2>(&args(&["--strcit", "a", "b"]), &["--strict"])
+        );
+        assert_eq!(
+            Err("unknown option: --lite".to_owned()),
+            positional::<1>(&args(&["--lite", "a"]), &[])
+        );
+        assert!(positional::<1>(&args(&["a", "b"]), &[]).is_err());
+    }
+}
@@ -17,7 +17,7 @@ pub fn err_type_constructor(_input: TokenStream) -> TokenStream {
             #[allow(non_snake_case)]
             #[inline]
             pub const fn #new_type_method_ident(text: &'static str) -> ErrType {
-                ErrType { text, flag: #flag_value }
+                ErrType { text, flag: #flag_value, name: "" }
             }
         });
     }
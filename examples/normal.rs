@@ -24,9 +24,9 @@ async fn get_user() -> Json<ApiResponse<User, DefaultMeta>> {
     Json(user.api_response_with_meta(DefaultMeta::new().with_request_id("abc-123")))
 }
 
-const EP_LV1: ErrPathRoot = ErrPathRoot::X00("product");
-const EP_LV2: ErrPathParent = EP_LV1.Y01("system");
-const EP_LV3: ErrPath = EP_LV2.Z20("module");
+const EP_LV1: ErrPathRoot = ErrPathRoot::x::<0>("product");
+const EP_LV2: ErrPathParent = EP_LV1.y::<1>("system");
+const EP_LV3: ErrPath = EP_LV2.z::<20>("module");
 
 /// get error
 #[handler]
@@ -0,0 +1,66 @@
+use axum::{
+    Json,
+    http::HeaderMap,
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+
+use crate::{ApiResponse, ResponseMeta};
+
+impl<Data, Meta> IntoResponse for ApiResponse<Data, Meta>
+where
+    Data: Serialize,
+    Meta: Serialize + ResponseMeta,
+{
+    /// The meta is filled from the meta context and emitted on the headers,
+    /// see [`ResponseMeta`].
+    fn into_response(mut self) -> Response {
+        if let ApiResponse::Error(error_response) = &self {
+            crate::error_code::counter::record_rendered(&error_response.error);
+            #[cfg(feature = "tracing")]
+            error_response.trace();
+        }
+        let mut headers = HeaderMap::new();
+        crate::meta::render_meta(&mut self, &mut headers);
+        (headers, Json(self)).into_response()
+    }
+}
+
+#[cfg(feature = "stream")]
+mod stream {
+    use axum::{
+        body::Body,
+        http::header::{CACHE_CONTROL, CONTENT_TYPE},
+        response::{IntoResponse, Response},
+    };
+    use futures_core::Stream;
+    use serde::Serialize;
+
+    use crate::stream::{NDJSON_CONTENT_TYPE, NdJsonStream, SSE_CONTENT_TYPE, SseStream, StreamFrame};
+
+    impl<F, T, Meta> IntoResponse for NdJsonStream<F>
+    where
+        F: Stream<Item = StreamFrame<T, Meta>> + Unpin + Send + 'static,
+        T: Serialize + 'static,
+        Meta: Serialize + 'static,
+    {
+        fn into_response(self) -> Response {
+            ([(CONTENT_TYPE, NDJSON_CONTENT_TYPE)], Body::from_stream(self)).into_response()
+        }
+    }
+
+    impl<F, T, Meta> IntoResponse for SseStream<F>
+    where
+        F: Stream<Item = StreamFrame<T, Meta>> + Unpin + Send + 'static,
+        T: Serialize + 'static,
+        Meta: Serialize + 'static,
+    {
+        fn into_response(self) -> Response {
+            (
+                [(CONTENT_TYPE, SSE_CONTENT_TYPE), (CACHE_CONTROL, "no-cache")],
+                Body::from_stream(self),
+            )
+                .into_response()
+        }
+    }
+}
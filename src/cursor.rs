@@ -0,0 +1,138 @@
+//! Signed opaque cursors for [`CursorPagination`](crate::CursorPagination).
+//!
+//! A cursor is `<payload>.<signature>`, both base64url without padding, and
+//! the signature is the HMAC-SHA256 of the payload, so the server only
+//! accepts the cursors it issued. The payload is signed, not encrypted.
+//!
+//! ```
+//! use api_response::{cursor::CursorSigner, prelude::*};
+//!
+//! let signer = CursorSigner::new(*b"a secret key of the server");
+//! let next_cursor = signer.encode(&(42u64, "2024-10-01"));
+//! let pagination = CursorPagination::default()
+//!     .with_next_cursor(Some(next_cursor.clone()))
+//!     .with_has_more(true);
+//! assert!(pagination.has_more);
+//! assert_eq!(Ok((42u64, "2024-10-01".to_owned())), signer.decode(&next_cursor));
+//! ```
+
+use std::fmt;
+
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+use hmac::{Hmac, Mac};
+use serde::{Serialize, de::DeserializeOwned};
+use sha2::Sha256;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// A possible error value when decoding a cursor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidCursor {
+    /// The cursor is not a signed payload, or its payload is not the expected
+    /// position.
+    Malformed,
+    /// The signature does not match the payload.
+    Signature,
+}
+
+impl fmt::Display for InvalidCursor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("malformed cursor"),
+            Self::Signature => f.write_str("invalid cursor signature"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidCursor {}
+
+/// Signs and verifies cursors with a server-side key.
+#[derive(Clone)]
+pub struct CursorSigner {
+    key: Vec<u8>,
+}
+
+impl fmt::Debug for CursorSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CursorSigner").finish_non_exhaustive()
+    }
+}
+
+impl CursorSigner {
+    #[inline]
+    pub fn new(key: impl Into<Vec<u8>>) -> Self {
+        Self { key: key.into() }
+    }
+
+    fn mac(&self, payload: &[u8]) -> HmacSha256 {
+        let mut mac = HmacSha256::new_from_slice(&self.key).expect("HMAC accepts keys of any length");
+        mac.update(payload);
+        mac
+    }
+
+    /// Sign a raw payload.
+    pub fn sign(&self, payload: &[u8]) -> String {
+        let signature = self.mac(payload).finalize().into_bytes();
+        format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(payload),
+            URL_SAFE_NO_PAD.encode(signature)
+        )
+    }
+
+    /// Verify a cursor and return its raw payload.
+    pub fn verify(&self, cursor: &str) -> Result<Vec<u8>, InvalidCursor> {
+        let (payload_part, signature_part) = cursor.split_once('.').ok_or(InvalidCursor::Malformed)?;
+        let payload = URL_SAFE_NO_PAD
+            .decode(payload_part)
+            .map_err(|_| InvalidCursor::Malformed)?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature_part)
+            .map_err(|_| InvalidCursor::Malformed)?;
+        self.mac(&payload)
+            .verify_slice(&signature)
+            .map_err(|_| InvalidCursor::Signature)?;
+        Ok(payload)
+    }
+
+    /// Sign the JSON of a position, e.g. the sort key of the last record.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the position fails to serialize to JSON, e.g. a map with
+    /// non-string keys.
+    pub fn encode<T: Serialize + ?Sized>(&self, position: &T) -> String {
+        self.sign(&serde_json::to_vec(position).expect("the cursor position serializes to JSON"))
+    }
+
+    /// Verify a cursor and deserialize its position.
+    pub fn decode<T: DeserializeOwned>(&self, cursor: &str) -> Result<T, InvalidCursor> {
+        serde_json::from_slice(&self.verify(cursor)?).map_err(|_| InvalidCursor::Malformed)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::default_numeric_fallback)]
+mod tests {
+    use super::{CursorSigner, InvalidCursor};
+
+    #[test]
+    fn sign_and_verify() {
+        let signer = CursorSigner::new("key");
+        let cursor = signer.encode(&[1, 2]);
+        assert!(!cursor.contains(['+', '/', '=']));
+        assert_eq!(Ok(vec![1, 2]), signer.decode::<Vec<u8>>(&cursor));
+        assert_eq!(
+            Err(InvalidCursor::Signature),
+            CursorSigner::new("other key").verify(&cursor)
+        );
+
+        let (_, signature) = cursor.split_once('.').unwrap();
+        let forged = format!("{}.{signature}", signer.sign(b"[9,9]").split_once('.').unwrap().0);
+        assert_eq!(Err(InvalidCursor::Signature), signer.verify(&forged));
+        assert_eq!(Err(InvalidCursor::Malformed), signer.verify("no-signature"));
+        assert_eq!(Err(InvalidCursor::Malformed), signer.decode::<String>(&cursor));
+        assert_eq!("CursorSigner { .. }", format!("{signer:?}"));
+    }
+}
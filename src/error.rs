@@ -1,8 +1,20 @@
-use std::{self, collections::HashMap, error::Error, fmt, sync::Arc};
+use std::{
+    self,
+    collections::HashMap,
+    error::Error,
+    fmt,
+    hash::{Hash, Hasher},
+    sync::Arc,
+};
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::{ApiResponse, MaybeString, utils::OrderedHashMap};
+use crate::{
+    ApiResponse, MaybeString,
+    error_code::{DefaultCodeLayout, ErrClass, ErrType},
+    meta::{ResponseMeta, merge_meta},
+    utils::OrderedHashMap,
+};
 
 /// Struct to represent an error response
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
@@ -27,12 +39,12 @@ impl<Meta> ErrorResponse<Meta> {
         ErrorResponse { error, meta: None }
     }
     #[inline(always)]
-    pub fn from_error_msg(code: impl Into<u32>, message: impl Into<String>) -> Self {
+    pub fn from_error_msg(code: impl Into<ErrCode>, message: impl Into<String>) -> Self {
         Self::from_error(ApiError::new(code, message))
     }
     #[inline(always)]
     pub fn from_error_source(
-        code: impl Into<u32>,
+        code: impl Into<ErrCode>,
         source: impl Error + Send + Sync + 'static,
         set_source_detail: bool,
         message: impl Into<MaybeString>,
@@ -49,6 +61,20 @@ impl<Meta> ErrorResponse<Meta> {
         self.meta = Some(meta);
         self
     }
+    /// Merge `meta` into the meta, see [`ResponseMeta::merge`].
+    pub fn merge_meta(&mut self, meta: Meta) -> &mut Self
+    where
+        Meta: ResponseMeta,
+    {
+        merge_meta(&mut self.meta, meta);
+        self
+    }
+    /// Serialize the error code in `format`, see [`CodeFormat`].
+    #[inline]
+    pub const fn with_code_format(mut self, format: CodeFormat) -> Self {
+        self.error.set_code_format(format);
+        self
+    }
     #[inline(always)]
     pub fn with_detail(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.set_detail(key, value);
@@ -70,10 +96,18 @@ impl<Meta> ErrorResponse<Meta> {
         self
     }
     #[inline]
-    pub const fn code(&self) -> u32 {
+    pub const fn code(&self) -> u64 {
         self.error.code()
     }
     #[inline]
+    pub fn err_type(&self) -> Option<ErrType> {
+        self.error.err_type()
+    }
+    #[inline]
+    pub fn class(&self) -> Option<ErrClass> {
+        self.error.class()
+    }
+    #[inline]
     pub const fn message(&self) -> &String {
         self.error.message()
     }
@@ -110,13 +144,156 @@ impl<Meta: fmt::Debug> Error for ErrorResponse<Meta> {
     }
 }
 
+/// The wire format of an error code, selected per envelope with
+/// [`ApiResponse::with_code_format`] or per error with
+/// [`ApiError::with_code_format`]. Both formats are always accepted when
+/// deserializing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodeFormat {
+    /// `"code": 1004000120`
+    #[default]
+    Numeric,
+    /// `"code": "PRODUCT_SYSTEM_USER_NOT_FOUND"`, falling back to the numeric
+    /// code when the error has no symbol.
+    Symbolic,
+}
+
+/// An error code: a `u64` number and an optional symbol such as
+/// `USER_NOT_FOUND`.
+///
+/// A code received as a string has the number 0. Codes are compared
+/// regardless of their wire format.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct ErrCode {
+    numeric: u64,
+    symbol: Option<Box<str>>,
+    format: CodeFormat,
+}
+
+impl PartialEq for ErrCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.numeric == other.numeric && self.symbol == other.symbol
+    }
+}
+
+impl Eq for ErrCode {}
+
+impl Hash for ErrCode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.numeric.hash(state);
+        self.symbol.hash(state);
+    }
+}
+
+impl ErrCode {
+    #[inline]
+    pub const fn new(numeric: u64) -> Self {
+        Self {
+            numeric,
+            symbol: None,
+            format: CodeFormat::Numeric,
+        }
+    }
+    #[inline]
+    pub const fn with_format(mut self, format: CodeFormat) -> Self {
+        self.format = format;
+        self
+    }
+    #[inline]
+    pub const fn format(&self) -> CodeFormat {
+        self.format
+    }
+    #[inline]
+    pub fn with_symbol(mut self, symbol: impl Into<MaybeString>) -> Self {
+        self.symbol = symbol.into().option_string().map(String::into_boxed_str);
+        self
+    }
+    #[inline]
+    pub const fn numeric(&self) -> u64 {
+        self.numeric
+    }
+    #[inline]
+    pub fn symbol(&self) -> Option<&str> {
+        self.symbol.as_deref()
+    }
+    /// Whether this is the code of a success in the `lite` envelope.
+    #[inline]
+    pub const fn is_zero(&self) -> bool {
+        self.numeric == 0 && self.symbol.is_none()
+    }
+    /// The `ErrType` of the leading four digits of the numeric code, for the
+    /// codes of [`DefaultCodeLayout`] and of any layout with more path digits,
+    /// e.g. four levels; only its flag is known.
+    pub fn err_type(&self) -> Option<ErrType> {
+        if self.numeric < DefaultCodeLayout::MIN_CODE {
+            return None;
+        }
+        let mut flag = self.numeric;
+        while flag >= 10_000 {
+            flag /= 10;
+        }
+        ErrType::try_from(flag).ok()
+    }
+}
+
+macro_rules! err_code_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for ErrCode {
+            #[inline]
+            fn from(numeric: $t) -> Self {
+                Self::new(u64::from(numeric))
+            }
+        }
+    )*};
+}
+err_code_from!(u8, u16, u32, u64);
+
+impl fmt::Display for ErrCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.symbol {
+            Some(symbol) if self.numeric == 0 => f.write_str(symbol),
+            _ => write!(f, "{}", self.numeric),
+        }
+    }
+}
+
+impl Serialize for ErrCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match (&self.symbol, self.format) {
+            (Some(symbol), CodeFormat::Symbolic) => serializer.serialize_str(symbol),
+            _ => serializer.serialize_u64(self.numeric),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ErrCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Wire {
+            Numeric(u64),
+            Symbolic(String),
+        }
+        Ok(match Wire::deserialize(deserializer)? {
+            Wire::Numeric(numeric) => Self::new(numeric),
+            Wire::Symbolic(symbol) => Self::new(0).with_symbol(symbol),
+        })
+    }
+}
+
 /// Struct to represent error information
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[derive(Serialize, Deserialize)]
 pub struct ApiError {
-    pub(crate) code: u32,
+    pub(crate) code: ErrCode,
     pub(crate) message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<HashMap<String, String>>))]
+    #[cfg_attr(feature = "schemars", schemars(with = "Option<HashMap<String, String>>"))]
     pub(crate) details: Option<OrderedHashMap<String, String>>,
     #[serde(skip)]
     pub(crate) source: Option<Arc<dyn Error + Send + Sync + 'static>>,
@@ -149,7 +326,7 @@ impl Error for ApiError {
 
 impl ApiError {
     #[inline(always)]
-    pub fn new(code: impl Into<u32>, message: impl Into<String>) -> Self {
+    pub fn new(code: impl Into<ErrCode>, message: impl Into<String>) -> Self {
         ApiError {
             code: code.into(),
             message: message.into(),
@@ -159,7 +336,7 @@ impl ApiError {
     }
     #[inline(always)]
     pub fn from_source(
-        code: impl Into<u32>,
+        code: impl Into<ErrCode>,
         source: impl Error + Send + Sync + 'static,
         set_source_detail: bool,
         message: impl Into<MaybeString>,
@@ -178,10 +355,26 @@ impl ApiError {
         }
         e
     }
-    pub fn with_code(mut self, code: impl Into<u32>) -> Self {
+    pub fn with_code(mut self, code: impl Into<ErrCode>) -> Self {
         self.code = code.into();
         self
     }
+    /// Set the symbolic code, keeping the numeric one.
+    pub fn with_symbol(mut self, symbol: impl Into<MaybeString>) -> Self {
+        self.code.symbol = symbol.into().option_string().map(String::into_boxed_str);
+        self
+    }
+    /// Serialize the code in `format`, see [`CodeFormat`].
+    #[inline]
+    pub const fn with_code_format(mut self, format: CodeFormat) -> Self {
+        self.set_code_format(format);
+        self
+    }
+    #[inline]
+    pub const fn set_code_format(&mut self, format: CodeFormat) -> &mut Self {
+        self.code.format = format;
+        self
+    }
     pub fn with_message(mut self, message: impl Into<String>) -> Self {
         self.message = message.into();
         self
@@ -222,9 +415,33 @@ impl ApiError {
         }
         self
     }
+    /// The numeric code.
+    #[inline]
+    pub const fn code(&self) -> u64 {
+        self.code.numeric
+    }
+    #[inline]
+    pub fn symbol(&self) -> Option<&str> {
+        self.code.symbol()
+    }
+    #[inline]
+    pub const fn err_code(&self) -> &ErrCode {
+        &self.code
+    }
+    /// The `ErrType` decoded from the numeric code; only its flag is known.
+    #[inline]
+    pub fn err_type(&self) -> Option<ErrType> {
+        self.code.err_type()
+    }
+    /// The classification of the decoded `ErrType`, see [`ErrType::class`].
+    #[inline]
+    pub fn class(&self) -> Option<ErrClass> {
+        self.err_type().map(|v| v.class())
+    }
+    /// Whether the request may be retried, given whether it is idempotent.
     #[inline]
-    pub const fn code(&self) -> u32 {
-        self.code
+    pub fn can_retry(&self, idempotent: bool) -> bool {
+        self.class().is_some_and(|v| v.can_retry(idempotent))
     }
     #[inline]
     pub const fn message(&self) -> &String {
@@ -270,11 +487,14 @@ impl ApiError {
 }
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::shadow_unrelated)]
+#[cfg_attr(not(feature = "flag-constructors"), allow(unused_imports))]
 mod tests {
     use crate::{
-        ApiError, ErrorResponse,
-        error_code::{ErrBrief, ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType},
+        ApiError, ErrCode, ErrorResponse,
+        error_code::{ErrBrief, ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType, ety_grpc},
     };
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn display() {
         const ET: ErrType = ErrType::T1100("The operation was cancelled.");
@@ -304,4 +524,44 @@ mod tests {
         let et2: ErrType = ET | "The request was cancelled.";
         assert_eq!("The request was cancelled.", et2.text());
     }
+
+    #[test]
+    fn err_code() {
+        const ED: ErrDecl =
+            ety_grpc::NOT_FOUND.declare(ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user-module 2"));
+        assert_eq!("PRODUCT_SYSTEM_USER_MODULE_2_NOT_FOUND", ED.symbol());
+        assert_eq!(
+            "X00_T1100",
+            (ErrType::t::<1100>("") + ErrPathRoot::x::<0>("").y::<0>("x00").z::<0>("")).symbol()
+        );
+
+        let api_error = ED.api_error();
+        assert_eq!(1004000120, api_error.code());
+        assert_eq!(Some("PRODUCT_SYSTEM_USER_MODULE_2_NOT_FOUND"), api_error.symbol());
+        assert_eq!(
+            r#"{"code":1004000120,"message":"Some requested entity was not found."}"#,
+            serde_json::to_string(&api_error).unwrap()
+        );
+
+        let large: ApiError = serde_json::from_str(r#"{"code":18446744073709551615,"message":""}"#).unwrap();
+        assert_eq!(u64::MAX, large.code());
+        let symbolic: ApiError = serde_json::from_str(r#"{"code":"USER_NOT_FOUND","message":""}"#).unwrap();
+        assert_eq!(&ErrCode::new(0).with_symbol("USER_NOT_FOUND"), symbolic.err_code());
+        assert_eq!(" ErrCode(USER_NOT_FOUND)", symbolic.to_string());
+        assert_eq!(None, symbolic.class());
+    }
+
+    #[test]
+    fn class() {
+        let ep = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        let unavailable = ErrorResponse::<()>::from_error(ety_grpc::UNAVAILABLE.declare(ep).api_error());
+        assert_eq!(
+            Some(ety_grpc::UNAVAILABLE.flag()),
+            unavailable.err_type().map(|v| v.flag())
+        );
+        assert_eq!(Some(ety_grpc::UNAVAILABLE.class()), unavailable.class());
+        assert!(unavailable.error.can_retry(false));
+        assert!(!ety_grpc::DEADLINE_EXCEEDED.declare(ep).api_error().can_retry(false));
+        assert!(!ApiError::new(404u32, "not found").can_retry(true));
+    }
 }
@@ -0,0 +1,222 @@
+//! A versioned, machine-readable error code catalog.
+//!
+//! Unlike `ErrDeclTally::json()`, every field is explicit, so the catalog can
+//! be loaded back and consumed by tools and clients in other languages.
+
+use std::fmt;
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::{DefaultCodeLayout, ErrClass, ErrDecl, ErrPathRoot, ErrType, intern};
+use crate::ErrCode;
+
+/// The version of the catalog format written by this crate.
+pub const CATALOG_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct Catalog {
+    pub version: u32,
+    /// Sorted by code.
+    pub entries: Vec<CatalogEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct CatalogEntry {
+    pub code: u64,
+    pub type_flag: u16,
+    pub type_text: String,
+    /// The name of the type, see `ErrType::name`; absent in older catalogs.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub type_name: String,
+    /// The symbolic code, see `ErrDecl::symbol`; absent in older catalogs.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub symbol: String,
+    /// The X, Y and Z segments of the path.
+    pub path: [CatalogPathSegment; 3],
+    pub http_status: u16,
+    /// The classification of the type; absent in older catalogs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ErrClass>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct CatalogPathSegment {
+    pub flag: u8,
+    pub name: String,
+}
+
+impl CatalogPathSegment {
+    pub fn new(flag: u8, name: impl Into<String>) -> Self {
+        Self {
+            flag,
+            name: name.into(),
+        }
+    }
+}
+
+impl CatalogEntry {
+    pub fn from_err_decl(err_decl: &ErrDecl) -> Self {
+        let err_path = err_decl.err_path();
+        let parent = err_path.parent();
+        let root = parent.root();
+        Self {
+            code: err_decl.extract().code(),
+            type_flag: err_decl.err_type().flag(),
+            type_text: err_decl.text().to_owned(),
+            type_name: err_decl.err_type().name().to_owned(),
+            symbol: err_decl.symbol(),
+            path: [
+                CatalogPathSegment::new(root.flag(), root.name()),
+                CatalogPathSegment::new(parent.flag(), parent.name()),
+                CatalogPathSegment::new(err_path.flag(), err_path.name()),
+            ],
+            http_status: StatusCode::from(*err_decl.err_type()).as_u16(),
+            class: Some(err_decl.err_type().class()),
+        }
+    }
+    /// Convert back into an `ErrDecl`, interning the texts and names, see
+    /// [`intern`].
+    pub fn err_decl(&self) -> Result<ErrDecl, CatalogError> {
+        let [x, y, z] = &self.path;
+        let err_type = ErrType::try_new(self.type_flag, &self.type_text)
+            .map_err(|_| CatalogError::InvalidEntry(self.code))?
+            .with_name(intern(&self.type_name));
+        let err_path = ErrPathRoot::try_from(x.flag, &x.name)
+            .and_then(|v| v.try_to_child(y.flag, &y.name))
+            .and_then(|v| v.try_to_child(z.flag, &z.name))
+            .map_err(|_| CatalogError::InvalidEntry(self.code))?;
+        let err_decl = err_type.declare(err_path);
+        if err_decl.code() == self.code {
+            return Ok(err_decl);
+        }
+        // The code of a layout with more path digits, see `CodeLayout::declare`.
+        let err_code = ErrCode::new(self.code);
+        if self.code > DefaultCodeLayout::MAX_CODE && err_code.err_type().map(|v| v.flag()) == Some(self.type_flag) {
+            Ok(ErrDecl::with_code(err_type, err_path, self.code))
+        } else {
+            Err(CatalogError::InvalidEntry(self.code))
+        }
+    }
+    /// The path in the display form, e.g. `X00(product)/Y01(system)/Z20(user)`.
+    pub fn path_text(&self) -> String {
+        let [x, y, z] = &self.path;
+        format!(
+            "X{:02}({})/Y{:02}({})/Z{:02}({})",
+            x.flag, x.name, y.flag, y.name, z.flag, z.name
+        )
+    }
+}
+
+impl Catalog {
+    /// Build a catalog of the current version; duplicated entries are removed.
+    pub fn from_err_decls<'a>(err_decls: impl IntoIterator<Item = &'a ErrDecl>) -> Self {
+        let mut entries: Vec<CatalogEntry> = err_decls.into_iter().map(CatalogEntry::from_err_decl).collect();
+        entries.sort();
+        entries.dedup();
+        Self {
+            version: CATALOG_VERSION,
+            entries,
+        }
+    }
+    /// Load a catalog, rejecting versions newer than this crate understands.
+    pub fn from_json(json: &str) -> Result<Self, CatalogError> {
+        let catalog: Self = serde_json::from_str(json).map_err(CatalogError::Json)?;
+        if catalog.version == 0 || catalog.version > CATALOG_VERSION {
+            return Err(CatalogError::UnsupportedVersion(catalog.version));
+        }
+        Ok(catalog)
+    }
+    /// Convert the entries back into `ErrDecl`s, e.g. to pass them to
+    /// [`register_err_decls`](super::tally::register_err_decls).
+    pub fn err_decls(&self) -> Result<Vec<ErrDecl>, CatalogError> {
+        self.entries.iter().map(CatalogEntry::err_decl).collect()
+    }
+    pub fn to_json(&self) -> String {
+        unsafe { serde_json::to_string_pretty(self).unwrap_unchecked() }
+    }
+}
+
+/// The error of loading a `Catalog`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CatalogError {
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+    /// The flags of the entry with this code are out of range, or do not
+    /// match its code.
+    InvalidEntry(u64),
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid catalog: {e}"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported catalog version {version}, expected 1 to {CATALOG_VERSION}"
+            ),
+            Self::InvalidEntry(code) => write!(f, "invalid catalog entry of code {code}"),
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+            Self::UnsupportedVersion(_) | Self::InvalidEntry(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{CATALOG_VERSION, Catalog, CatalogError};
+    use crate::error_code::{ErrPath, ErrPathRoot, ety_grpc};
+
+    #[test]
+    fn round_trip() {
+        const EP: ErrPath = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        let err_decls = [
+            ety_grpc::NOT_FOUND.declare(EP),
+            ety_grpc::INVALID_ARGUMENT.declare(EP),
+            ety_grpc::NOT_FOUND.declare(EP),
+        ];
+        let catalog = Catalog::from_err_decls(&err_decls);
+        assert_eq!(CATALOG_VERSION, catalog.version);
+        assert_eq!(2, catalog.entries.len());
+        let entry = &catalog.entries[1];
+        assert_eq!(1004000120, entry.code);
+        assert_eq!(1004, entry.type_flag);
+        assert_eq!(404, entry.http_status);
+        assert_eq!("X00(product)/Y01(system)/Z20(user)", entry.path_text());
+
+        let json = catalog.to_json();
+        assert!(json.contains(r#""typeText": "Some requested entity was not found.""#));
+        assert!(json.contains(r#""symbol": "PRODUCT_SYSTEM_USER_NOT_FOUND""#));
+        assert!(json.contains(r#""fault": "client""#));
+        assert_eq!(catalog, Catalog::from_json(&json).unwrap());
+
+        let future = json.replacen(r#""version": 1"#, r#""version": 99"#, 1);
+        assert!(matches!(
+            Catalog::from_json(&future),
+            Err(CatalogError::UnsupportedVersion(99))
+        ));
+        assert!(matches!(Catalog::from_json("{}"), Err(CatalogError::Json(_))));
+
+        let mut loaded = Catalog::from_json(&json).unwrap();
+        assert_eq!(vec![err_decls[1], err_decls[0]], loaded.err_decls().unwrap());
+        loaded.entries[0].code += 1;
+        assert!(matches!(
+            loaded.err_decls(),
+            Err(CatalogError::InvalidEntry(1002000121))
+        ));
+    }
+}
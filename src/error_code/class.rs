@@ -0,0 +1,207 @@
+use std::fmt;
+
+use http::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::{ErrType, ety_grpc};
+
+/// Which side of the call caused the error.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Fault {
+    Client,
+    #[default]
+    Server,
+}
+
+/// The level at which an error is worth logging.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum Severity {
+    Info,
+    Warn,
+    #[default]
+    Error,
+    Critical,
+}
+
+/// The classification of an `ErrType`, see [`ErrType::class`].
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize
+)]
+#[serde(rename_all = "camelCase")]
+#[non_exhaustive]
+pub struct ErrClass {
+    pub fault: Fault,
+    pub severity: Severity,
+    /// Whether the same request may succeed when retried later.
+    pub retryable: bool,
+    /// Whether a retry is only safe for idempotent requests, because the
+    /// failed request may have taken effect.
+    pub idempotent_only: bool,
+}
+
+impl ErrClass {
+    #[inline]
+    pub const fn new(fault: Fault, severity: Severity) -> Self {
+        Self {
+            fault,
+            severity,
+            retryable: false,
+            idempotent_only: false,
+        }
+    }
+    /// Mark the error as retryable for any request.
+    #[inline]
+    pub const fn retryable(mut self) -> Self {
+        self.retryable = true;
+        self.idempotent_only = false;
+        self
+    }
+    /// Mark the error as retryable for idempotent requests only.
+    #[inline]
+    pub const fn retryable_if_idempotent(mut self) -> Self {
+        self.retryable = true;
+        self.idempotent_only = true;
+        self
+    }
+    /// Whether a request may be retried, given whether it is idempotent.
+    #[inline]
+    pub const fn can_retry(&self, idempotent: bool) -> bool {
+        self.retryable && (idempotent || !self.idempotent_only)
+    }
+    /// The classification derived from an HTTP status.
+    pub fn from_status(status: StatusCode) -> Self {
+        match status.as_u16() {
+            408 => Self::new(Fault::Client, Severity::Info).retryable(),
+            429 => Self::new(Fault::Client, Severity::Warn).retryable(),
+            401 | 403 => Self::new(Fault::Client, Severity::Warn),
+            502 | 504 => Self::new(Fault::Server, Severity::Error).retryable_if_idempotent(),
+            503 => Self::new(Fault::Server, Severity::Error).retryable(),
+            _ if status.is_client_error() => Self::new(Fault::Client, Severity::Info),
+            _ => Self::new(Fault::Server, Severity::Error),
+        }
+    }
+}
+
+impl fmt::Display for ErrClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fault = match self.fault {
+            Fault::Client => "client",
+            Fault::Server => "server",
+        };
+        let severity = match self.severity {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Error => "error",
+            Severity::Critical => "critical",
+        };
+        write!(f, "{fault}, {severity}")?;
+        match (self.retryable, self.idempotent_only) {
+            (true, true) => f.write_str(", retryable if idempotent"),
+            (true, false) => f.write_str(", retryable"),
+            (false, _) => Ok(()),
+        }
+    }
+}
+
+impl ErrType {
+    /// The classification of the type: the `ety_grpc` types are classified
+    /// after the gRPC retry guidance, the other ones are derived from their
+    /// HTTP status, see [`status_map`](super::status_map).
+    pub fn class(&self) -> ErrClass {
+        use Fault::{Client, Server};
+        use Severity::{Critical, Error, Info, Warn};
+        match self.flag() {
+            v if v == ety_grpc::CANCELLED.flag() => ErrClass::new(Client, Info),
+            v if v == ety_grpc::UNKNOWN.flag() => ErrClass::new(Server, Error),
+            v if v == ety_grpc::INVALID_ARGUMENT.flag() => ErrClass::new(Client, Info),
+            v if v == ety_grpc::DEADLINE_EXCEEDED.flag() => ErrClass::new(Server, Warn).retryable_if_idempotent(),
+            v if v == ety_grpc::NOT_FOUND.flag() => ErrClass::new(Client, Info),
+            v if v == ety_grpc::ALREADY_EXISTS.flag() => ErrClass::new(Client, Info),
+            v if v == ety_grpc::PERMISSION_DENIED.flag() => ErrClass::new(Client, Warn),
+            v if v == ety_grpc::RESOURCE_EXHAUSTED.flag() => ErrClass::new(Client, Warn).retryable(),
+            v if v == ety_grpc::FAILED_PRECONDITION.flag() => ErrClass::new(Client, Info),
+            v if v == ety_grpc::ABORTED.flag() => ErrClass::new(Client, Warn).retryable(),
+            v if v == ety_grpc::OUT_OF_RANGE.flag() => ErrClass::new(Client, Info),
+            v if v == ety_grpc::UNIMPLEMENTED.flag() => ErrClass::new(Server, Error),
+            v if v == ety_grpc::INTERNAL.flag() => ErrClass::new(Server, Error),
+            v if v == ety_grpc::UNAVAILABLE.flag() => ErrClass::new(Server, Error).retryable(),
+            v if v == ety_grpc::DATA_LOSS.flag() => ErrClass::new(Server, Critical),
+            v if v == ety_grpc::UNAUTHENTICATED.flag() => ErrClass::new(Client, Warn),
+            _ => ErrClass::from_status(StatusCode::from(*self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrClass, Fault, Severity};
+    use crate::error_code::{ety_biz, ety_grpc, ety_http};
+
+    #[test]
+    fn classify() {
+        let unavailable = ety_grpc::UNAVAILABLE.class();
+        assert_eq!(Fault::Server, unavailable.fault);
+        assert!(unavailable.can_retry(false));
+        assert_eq!("server, error, retryable", unavailable.to_string());
+
+        let deadline = ety_grpc::DEADLINE_EXCEEDED.class();
+        assert!(deadline.can_retry(true));
+        assert!(!deadline.can_retry(false));
+        assert_eq!("server, warn, retryable if idempotent", deadline.to_string());
+
+        assert_eq!(
+            ErrClass::new(Fault::Client, Severity::Info),
+            ety_grpc::NOT_FOUND.class()
+        );
+        assert_eq!(Severity::Critical, ety_grpc::DATA_LOSS.class().severity);
+        assert!(ety_http::TOO_MANY_REQUESTS.class().retryable);
+        assert!(ety_biz::QUOTA_EXCEEDED.class().retryable);
+        assert_eq!(Fault::Client, ety_biz::VALIDATION_FAILED.class().fault);
+        assert_eq!(Fault::Server, ety_http::BAD_GATEWAY.class().fault);
+        assert!(ety_http::BAD_GATEWAY.class().idempotent_only);
+        assert_eq!(ErrClass::new(Fault::Server, Severity::Error).retryable(), unavailable);
+        assert!(
+            !ErrClass::new(Fault::Server, Severity::Warn)
+                .retryable_if_idempotent()
+                .can_retry(false)
+        );
+    }
+}
@@ -0,0 +1,240 @@
+//! Counters of the error codes that fire, joined with [`ErrDeclTally`] to
+//! find the hot codes and the declared codes that are never seen.
+//!
+//! Counting is off until [`enable`] is called, and then happens at one
+//! [`CountPoint`] so that an error is never counted twice:
+//!
+//! ```
+//! use api_response::error_code::{
+//!     ErrPathRoot,
+//!     counter::{self, CountPoint},
+//!     ety_grpc,
+//!     tally::tally_err_decl,
+//! };
+//!
+//! counter::enable(CountPoint::Evaluate);
+//! let _ = api_response::api_err!(ety_grpc::NOT_FOUND, ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user"));
+//! let report = tally_err_decl().occurrences(&counter::snapshot());
+//! assert_eq!(1, report.hot[0].1);
+//! ```
+
+#[cfg(feature = "metrics")]
+use std::collections::HashMap;
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::{
+        PoisonError, RwLock,
+        atomic::{AtomicU8, AtomicU64, Ordering},
+    },
+};
+
+use super::{ErrDecl, tally::ErrDeclTally};
+use crate::ApiError;
+
+/// Where errors are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CountPoint {
+    /// When `api_err!` evaluates.
+    Evaluate,
+    /// When an `ErrorResponse` is rendered by the `salvo` or `axum`
+    /// integrations, or passed to [`record_rendered`].
+    Render,
+}
+
+const DISABLED: u8 = 0;
+
+static COUNT_POINT: AtomicU8 = AtomicU8::new(DISABLED);
+
+static COUNTS: RwLock<BTreeMap<u64, AtomicU64>> = RwLock::new(BTreeMap::new());
+
+/// Start counting errors at the given point.
+pub fn enable(point: CountPoint) {
+    let value = match point {
+        CountPoint::Evaluate => 1,
+        CountPoint::Render => 2,
+    };
+    COUNT_POINT.store(value, Ordering::Relaxed);
+}
+
+/// Stop counting errors; the counts are kept.
+pub fn disable() {
+    COUNT_POINT.store(DISABLED, Ordering::Relaxed);
+}
+
+/// The point errors are counted at, if counting is enabled.
+pub fn count_point() -> Option<CountPoint> {
+    match COUNT_POINT.load(Ordering::Relaxed) {
+        1 => Some(CountPoint::Evaluate),
+        2 => Some(CountPoint::Render),
+        _ => None,
+    }
+}
+
+fn record(point: CountPoint, error: &ApiError) {
+    let code = error.code();
+    if code == 0 || count_point() != Some(point) {
+        return;
+    }
+    if let Some(count) = COUNTS.read().unwrap_or_else(PoisonError::into_inner).get(&code) {
+        count.fetch_add(1, Ordering::Relaxed);
+        return;
+    }
+    COUNTS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .entry(code)
+        .or_default()
+        .fetch_add(1, Ordering::Relaxed);
+}
+
+/// Count an error evaluated by `api_err!`.
+#[doc(hidden)]
+pub fn record_evaluated(error: ApiError) -> ApiError {
+    record(CountPoint::Evaluate, &error);
+    error
+}
+
+/// Count a rendered error, for integrations other than `salvo` and `axum`.
+pub fn record_rendered(error: &ApiError) {
+    record(CountPoint::Render, error);
+}
+
+/// The counts of the codes seen so far.
+pub fn snapshot() -> CounterSnapshot {
+    let counts = COUNTS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .map(|(code, count)| (*code, count.load(Ordering::Relaxed)))
+        .collect();
+    CounterSnapshot { counts }
+}
+
+/// Clear the counts.
+pub fn reset() {
+    COUNTS.write().unwrap_or_else(PoisonError::into_inner).clear();
+}
+
+/// The counts of the codes at one point in time.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CounterSnapshot {
+    /// The number of occurrences by code.
+    pub counts: BTreeMap<u64, u64>,
+}
+
+impl CounterSnapshot {
+    #[inline]
+    pub fn count(&self, code: u64) -> u64 {
+        self.counts.get(&code).copied().unwrap_or_default()
+    }
+}
+
+/// The occurrences of the declared codes, see
+/// [`ErrDeclTally::occurrences`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct OccurrenceReport {
+    /// The declarations that were seen with their count, the most frequent
+    /// first.
+    pub hot: Vec<(ErrDecl, u64)>,
+    /// The declarations that were never seen.
+    pub unseen: Vec<ErrDecl>,
+    /// The codes that were seen but are not declared, with their count.
+    pub undeclared: Vec<(u64, u64)>,
+}
+
+impl ErrDeclTally {
+    /// Join a snapshot of the counters with the unique declarations.
+    ///
+    /// Declarations sharing a code, e.g. with different texts, share its
+    /// count.
+    pub fn occurrences(&self, snapshot: &CounterSnapshot) -> OccurrenceReport {
+        let mut report = OccurrenceReport::default();
+        let mut declared = HashSet::new();
+        for err_decl in self.unique() {
+            let code = err_decl.extract().code();
+            declared.insert(code);
+            match snapshot.count(code) {
+                0 => report.unseen.push(err_decl),
+                count => report.hot.push((err_decl, count)),
+            }
+        }
+        report.hot.sort_by(|a, b| {
+            b.1.cmp(&a.1)
+                .then_with(|| a.0.extract().code().cmp(&b.0.extract().code()))
+        });
+        report.unseen.sort_by_key(|v| v.extract().code());
+        report.undeclared = snapshot
+            .counts
+            .iter()
+            .filter(|(code, _)| !declared.contains(*code))
+            .map(|(code, count)| (*code, *count))
+            .collect();
+        report
+    }
+}
+
+/// Export the counts to the `metrics` recorder as the absolute value of the
+/// `api_response_errors_total` counter, labeled by `code`, `type` and the
+/// `x`, `y` and `z` path names of the declaration.
+///
+/// Call it periodically, or before the recorder is scraped. Undeclared codes
+/// have empty path labels.
+#[cfg(feature = "metrics")]
+pub fn export_metrics(tally: &ErrDeclTally) {
+    let snapshot = snapshot();
+    let mut decls: HashMap<u64, ErrDecl> = HashMap::new();
+    for err_decl in tally.unique() {
+        decls.entry(err_decl.extract().code()).or_insert(err_decl);
+    }
+    for (code, count) in &snapshot.counts {
+        let (err_type, [x, y, z]) = decls.get(code).map_or_else(
+            || (String::new(), ["", "", ""]),
+            |v| {
+                let path = v.err_path();
+                let parent = path.parent();
+                (
+                    format!("T{:04}", v.err_type().flag()),
+                    [parent.root().name(), parent.name(), path.name()],
+                )
+            },
+        );
+        metrics::counter!(
+            "api_response_errors_total",
+            "code" => code.to_string(),
+            "type" => err_type,
+            "x" => x,
+            "y" => y,
+            "z" => z
+        )
+        .absolute(*count);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CounterSnapshot, OccurrenceReport};
+    use crate::error_code::{ErrPath, ErrPathRoot, ety_grpc, tally::ErrDeclTally};
+
+    #[test]
+    fn occurrences() {
+        const EP: ErrPath = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        let not_found = ety_grpc::NOT_FOUND.declare(EP);
+        let invalid = ety_grpc::INVALID_ARGUMENT.declare(EP);
+        let internal = ety_grpc::INTERNAL.declare(EP);
+        let tally = ErrDeclTally::from_iter([not_found, invalid, internal]);
+        let snapshot = CounterSnapshot {
+            counts: [(1004000120, 2), (1002000120, 5), (404, 1)].into(),
+        };
+        assert_eq!(
+            OccurrenceReport {
+                hot: vec![(invalid, 5), (not_found, 2)],
+                unseen: vec![internal],
+                undeclared: vec![(404, 1)],
+            },
+            tally.occurrences(&snapshot)
+        );
+    }
+}
@@ -1,5 +1,8 @@
+#[cfg(feature = "flag-constructors")]
 use api_response_macros::ErrPathConstructor;
 
+use super::IntoStaticStr;
+
 #[derive(
     Debug,
     Clone,
@@ -11,9 +14,9 @@ use api_response_macros::ErrPathConstructor;
     Hash,
     getset2::Getset2,
     serde::Serialize,
-    serde::Deserialize,
-    ErrPathConstructor
+    serde::Deserialize
 )]
+#[cfg_attr(feature = "flag-constructors", derive(ErrPathConstructor))]
 #[getset2(get_copy(pub, const))]
 #[non_exhaustive]
 pub struct ErrPathRoot {
@@ -32,9 +35,9 @@ pub struct ErrPathRoot {
     Hash,
     getset2::Getset2,
     serde::Serialize,
-    serde::Deserialize,
-    ErrPathConstructor
+    serde::Deserialize
 )]
+#[cfg_attr(feature = "flag-constructors", derive(ErrPathConstructor))]
 #[getset2(get_copy(pub, const))]
 #[non_exhaustive]
 pub struct ErrPathParent {
@@ -70,6 +73,40 @@ impl ErrPathRoot {
     pub const fn default() -> Self {
         Self { name: "", flag: 0 }
     }
+    /// Create an `ErrPathRoot` whose flag is checked at compile time, e.g.
+    /// `ErrPathRoot::x::<0>("product")`.
+    #[inline]
+    pub const fn x<const FLAG: u8>(name: &'static str) -> Self {
+        const { assert!(FLAG <= 99, "the flag of an ErrPath level must be from 0 to 99") };
+        Self { name, flag: FLAG }
+    }
+    /// # Panics
+    ///
+    /// Panics when the flag is greater than 99.
+    #[inline]
+    pub const fn new_checked(flag: u8, name: &'static str) -> Self {
+        assert!(flag <= 99, "the flag of an ErrPath level must be from 0 to 99");
+        Self { name, flag }
+    }
+    /// Create a child whose flag is checked at compile time, e.g.
+    /// `ErrPathRoot::x::<0>("product").y::<1>("system")`.
+    #[inline]
+    pub const fn y<const FLAG: u8>(self, name: &'static str) -> ErrPathParent {
+        const { assert!(FLAG <= 99, "the flag of an ErrPath level must be from 0 to 99") };
+        ErrPathParent {
+            root: self,
+            name,
+            flag: FLAG,
+        }
+    }
+    /// # Panics
+    ///
+    /// Panics when the flag is greater than 99.
+    #[inline]
+    pub const fn to_child_checked(self, flag: u8, name: &'static str) -> ErrPathParent {
+        assert!(flag <= 99, "the flag of an ErrPath level must be from 0 to 99");
+        ErrPathParent { root: self, name, flag }
+    }
     #[inline]
     pub fn path(&self) -> String {
         self.to_string()
@@ -78,18 +115,23 @@ impl ErrPathRoot {
     pub const fn path_flag(&self) -> u32 {
         self.flag as u32
     }
-    pub fn try_from<T: TryInto<u8>>(flag: T, name: &'static str) -> Result<Self, InvalidErrPathFlag> {
+    /// Owned names are interned, see [`intern`](super::intern).
+    pub fn try_from<T: TryInto<u8>>(flag: T, name: impl IntoStaticStr) -> Result<Self, InvalidErrPathFlag> {
         let x: u8 = flag.try_into().map_err(|_| InvalidErrPathFlag::new())?;
         if x > 99 {
             Err(InvalidErrPathFlag::new())
         } else {
-            Ok(Self { name, flag: x })
+            Ok(Self {
+                name: name.into_static_str(),
+                flag: x,
+            })
         }
     }
+    /// Owned names are interned, see [`intern`](super::intern).
     pub fn try_to_child<T: TryInto<u8>>(
         self,
         child_flag: T,
-        child_name: &'static str,
+        child_name: impl IntoStaticStr,
     ) -> Result<ErrPathParent, InvalidErrPathFlag> {
         let x: u8 = child_flag.try_into().map_err(|_| InvalidErrPathFlag::new())?;
         if x > 99 {
@@ -97,7 +139,7 @@ impl ErrPathRoot {
         } else {
             Ok(ErrPathParent {
                 root: self,
-                name: child_name,
+                name: child_name.into_static_str(),
                 flag: x,
             })
         }
@@ -112,6 +154,29 @@ impl ErrPathParent {
             flag: 0,
         }
     }
+    /// Create a child whose flag is checked at compile time, e.g.
+    /// `ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user")`.
+    #[inline]
+    pub const fn z<const FLAG: u8>(self, name: &'static str) -> ErrPath {
+        const { assert!(FLAG <= 99, "the flag of an ErrPath level must be from 0 to 99") };
+        ErrPath {
+            parent: self,
+            name,
+            flag: FLAG,
+        }
+    }
+    /// # Panics
+    ///
+    /// Panics when the flag is greater than 99.
+    #[inline]
+    pub const fn to_child_checked(self, flag: u8, name: &'static str) -> ErrPath {
+        assert!(flag <= 99, "the flag of an ErrPath level must be from 0 to 99");
+        ErrPath {
+            parent: self,
+            name,
+            flag,
+        }
+    }
     #[inline]
     pub fn path(&self) -> String {
         self.to_string()
@@ -120,10 +185,11 @@ impl ErrPathParent {
     pub const fn path_flag(&self) -> u32 {
         (self.root.path_flag() * 100) + self.flag as u32
     }
+    /// Owned names are interned, see [`intern`](super::intern).
     pub fn try_to_child<T: TryInto<u8>>(
         self,
         child_flag: T,
-        child_name: &'static str,
+        child_name: impl IntoStaticStr,
     ) -> Result<ErrPath, InvalidErrPathFlag> {
         let x: u8 = child_flag.try_into().map_err(|_| InvalidErrPathFlag::new())?;
         if x > 99 {
@@ -131,7 +197,7 @@ impl ErrPathParent {
         } else {
             Ok(ErrPath {
                 parent: self,
-                name: child_name,
+                name: child_name.into_static_str(),
                 flag: x,
             })
         }
@@ -154,6 +220,11 @@ impl ErrPath {
     pub const fn path_flag(&self) -> u32 {
         (self.parent.path_flag() * 100) + self.flag as u32
     }
+    /// The flags of the X, Y and Z levels.
+    #[inline]
+    pub const fn flags(&self) -> [u64; 3] {
+        [self.parent.root.flag as u64, self.parent.flag as u64, self.flag as u64]
+    }
 }
 
 impl std::fmt::Display for ErrPathRoot {
@@ -202,9 +273,11 @@ impl std::fmt::Display for InvalidErrPathFlag {
 impl std::error::Error for InvalidErrPathFlag {}
 
 #[cfg(test)]
+#[allow(clippy::default_numeric_fallback)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn useage() {
         const ERR_PATH_ROOT: ErrPathRoot = ErrPathRoot::X99("name1");
@@ -215,6 +288,7 @@ mod tests {
         assert_eq!("X99(name1)/Y01(name2)", ERR_PATH_PARENT.path());
         assert_eq!("X99(name1)/Y01(name2)/Z20(name3)", ERR_PATH.path());
     }
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn convert() {
         assert_eq!(Ok(ErrPathRoot::X00("")), ErrPathRoot::try_from(0, ""));
@@ -241,4 +315,46 @@ mod tests {
             ErrPathRoot::X99("").Y99("").try_to_child(99, "")
         );
     }
+    #[test]
+    fn xyz() {
+        const ERR_PATH_ROOT: ErrPathRoot = ErrPathRoot::x::<99>("name1");
+        const ERR_PATH_PARENT: ErrPathParent = ERR_PATH_ROOT.y::<1>("name2");
+        const ERR_PATH: ErrPath = ERR_PATH_PARENT.z::<20>("name3");
+
+        assert_eq!("X99(name1)", ERR_PATH_ROOT.path());
+        assert_eq!("X99(name1)/Y01(name2)", ERR_PATH_PARENT.path());
+        assert_eq!("X99(name1)/Y01(name2)/Z20(name3)", ERR_PATH.path());
+        assert_eq!(Ok(ErrPathRoot::x::<0>("")), ErrPathRoot::try_from(0, ""));
+        assert_eq!(
+            Ok(ErrPathRoot::x::<99>("").y::<99>("")),
+            ErrPathRoot::x::<99>("").try_to_child(99, "")
+        );
+        assert_eq!(
+            Ok(ErrPathRoot::x::<99>("").y::<99>("").z::<99>("")),
+            ErrPathRoot::x::<99>("").y::<99>("").try_to_child(99, "")
+        );
+    }
+    #[test]
+    fn new_checked() {
+        const ROOT: ErrPathRoot = ErrPathRoot::new_checked(99, "a");
+        assert_eq!(ErrPathRoot::x::<99>("a"), ROOT);
+        assert!(std::panic::catch_unwind(|| ErrPathRoot::new_checked(100, "")).is_err());
+    }
+    #[test]
+    fn to_child_checked() {
+        const EP: ErrPath = ErrPathRoot::x::<99>("a")
+            .to_child_checked(0, "b")
+            .to_child_checked(20, "c");
+        assert_eq!(ErrPathRoot::x::<99>("a").y::<0>("b").z::<20>("c"), EP);
+        assert!(std::panic::catch_unwind(|| ErrPathRoot::default().to_child_checked(100, "")).is_err());
+        assert!(std::panic::catch_unwind(|| ErrPathParent::default().to_child_checked(100, "")).is_err());
+    }
+    #[cfg(feature = "flag-constructors")]
+    #[test]
+    fn flag_constructors() {
+        assert_eq!(
+            ErrPathRoot::x::<99>("a").y::<0>("b").z::<20>("c"),
+            ErrPathRoot::X99("a").Y00("b").Z20("c")
+        );
+    }
 }
@@ -1,6 +1,7 @@
+#[cfg(feature = "flag-constructors")]
 use api_response_macros::ErrTypeConstructor;
 
-use super::{ErrBrief, ErrDecl, ErrPath};
+use super::{ErrBrief, ErrDecl, ErrPath, IntoStaticStr};
 use crate::ApiError;
 
 #[derive(
@@ -14,19 +15,58 @@ use crate::ApiError;
     Hash,
     getset2::Getset2,
     serde::Serialize,
-    serde::Deserialize,
-    ErrTypeConstructor
+    serde::Deserialize
 )]
+#[cfg_attr(feature = "flag-constructors", derive(ErrTypeConstructor))]
 #[non_exhaustive]
 #[getset2(get_copy(pub, const))]
 pub struct ErrType {
     #[getset2(set(pub, const), set_with(pub, const))]
     text: &'static str,
-    /// The value range of the flag is from 1000 to 4293 inclusive.
+    /// The value range of the flag is from 1000 to 4293 inclusive, also with
+    /// `u64` codes: the codes of
+    /// [`DefaultCodeLayout`](super::DefaultCodeLayout) then still fit in a
+    /// `u32`.
     flag: u16,
+    /// The symbolic name, e.g. `NOT_FOUND`, used to build the symbolic code
+    /// of an `ErrDecl`. When empty, `T{flag}` is used.
+    #[getset2(set(pub, const), set_with(pub, const))]
+    name: &'static str,
 }
 
 impl ErrType {
+    /// Create an `ErrType` whose flag is checked at compile time, e.g.
+    /// `ErrType::t::<1100>("The operation was cancelled.")`.
+    ///
+    /// A flag out of `1000..=4293` fails the build.
+    #[inline]
+    pub const fn t<const FLAG: u16>(text: &'static str) -> Self {
+        const {
+            assert!(
+                FLAG >= 1000 && FLAG <= 4293,
+                "the flag of an ErrType must be from 1000 to 4293"
+            )
+        };
+        Self {
+            text,
+            flag: FLAG,
+            name: "",
+        }
+    }
+    /// Create an `ErrType` from a flag known in a const context.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the flag is out of `1000..=4293`, which fails the build
+    /// when evaluated in a const context.
+    #[inline]
+    pub const fn new_checked(flag: u16, text: &'static str) -> Self {
+        assert!(
+            flag >= 1000 && flag <= 4293,
+            "the flag of an ErrType must be from 1000 to 4293"
+        );
+        Self { text, flag, name: "" }
+    }
     #[inline(always)]
     pub const fn declare(self, err_path: ErrPath) -> ErrDecl {
         ErrDecl::new(self, err_path)
@@ -37,7 +77,21 @@ impl ErrType {
     }
     #[inline(always)]
     pub fn api_error(self, err_path: &ErrPath) -> ApiError {
-        self.extract(err_path).api_error()
+        self.declare(*err_path).api_error()
+    }
+    /// The symbolic name, falling back to `T{flag}` when no name is set.
+    pub fn symbol_name(&self) -> String {
+        if self.name.is_empty() {
+            format!("T{:04}", self.flag)
+        } else {
+            self.name.to_owned()
+        }
+    }
+    /// Create an `ErrType` at runtime; owned texts are interned, see
+    /// [`intern`](super::intern).
+    pub fn try_new<T: TryInto<u16>>(flag: T, text: impl IntoStaticStr) -> Result<Self, InvalidErrTypeFlag> {
+        let digit: u16 = flag.try_into().map_err(|_| InvalidErrTypeFlag::new())?;
+        Ok(Self::try_from(digit)?.with_text(text.into_static_str()))
     }
 }
 
@@ -88,6 +142,7 @@ impl std::convert::TryFrom<i16> for ErrType {
             Ok(ErrType {
                 text: "",
                 flag: digit as u16,
+                name: "",
             })
         } else {
             Err(InvalidErrTypeFlag::new())
@@ -100,7 +155,11 @@ impl std::convert::TryFrom<u16> for ErrType {
     #[allow(clippy::cast_sign_loss)]
     fn try_from(digit: u16) -> Result<Self, Self::Error> {
         if (1000..=4293).contains(&digit) {
-            Ok(ErrType { text: "", flag: digit })
+            Ok(ErrType {
+                text: "",
+                flag: digit,
+                name: "",
+            })
         } else {
             Err(InvalidErrTypeFlag::new())
         }
@@ -116,6 +175,7 @@ impl std::convert::TryFrom<i32> for ErrType {
             Ok(ErrType {
                 text: "",
                 flag: digit as u16,
+                name: "",
             })
         } else {
             Err(InvalidErrTypeFlag::new())
@@ -132,6 +192,7 @@ impl std::convert::TryFrom<u32> for ErrType {
             Ok(ErrType {
                 text: "",
                 flag: digit as u16,
+                name: "",
             })
         } else {
             Err(InvalidErrTypeFlag::new())
@@ -148,6 +209,7 @@ impl std::convert::TryFrom<i64> for ErrType {
             Ok(ErrType {
                 text: "",
                 flag: digit as u16,
+                name: "",
             })
         } else {
             Err(InvalidErrTypeFlag::new())
@@ -164,6 +226,7 @@ impl std::convert::TryFrom<u64> for ErrType {
             Ok(ErrType {
                 text: "",
                 flag: digit as u16,
+                name: "",
             })
         } else {
             Err(InvalidErrTypeFlag::new())
@@ -172,9 +235,11 @@ impl std::convert::TryFrom<u64> for ErrType {
 }
 
 #[cfg(test)]
+#[allow(clippy::default_numeric_fallback)]
 mod tests {
     use super::{ErrType, InvalidErrTypeFlag};
 
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn display() {
         const ET1: ErrType = ErrType::T4293("");
@@ -182,6 +247,7 @@ mod tests {
         const ET2: ErrType = ErrType::T1000("The operation was cancelled.");
         assert_eq!("The operation was cancelled. ErrType(1000)", ET2.to_string());
     }
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn convert() {
         assert_eq!(Err(InvalidErrTypeFlag::new()), ErrType::try_from(0));
@@ -191,4 +257,30 @@ mod tests {
         assert_eq!(Err(InvalidErrTypeFlag::new()), ErrType::try_from(999));
         assert_eq!(Err(InvalidErrTypeFlag::new()), ErrType::try_from(4294));
     }
+    #[test]
+    fn t() {
+        const ET1: ErrType = ErrType::t::<4293>("");
+        assert_eq!("<no description> ErrType(4293)", ET1.to_string());
+        const ET2: ErrType = ErrType::t::<1000>("The operation was cancelled.");
+        assert_eq!("The operation was cancelled. ErrType(1000)", ET2.to_string());
+        assert_eq!(Ok(ErrType::t::<1000>("")), ErrType::try_from(1000));
+        assert_eq!(Ok(ErrType::t::<4293>("")), ErrType::try_from(4293));
+        assert_eq!(
+            Ok(ErrType::t::<1004>("Not found.")),
+            ErrType::try_new(1004, "Not found.".to_owned())
+        );
+        assert_eq!(Err(InvalidErrTypeFlag::new()), ErrType::try_new(-1, "Not found."));
+    }
+    #[test]
+    fn new_checked() {
+        const ET: ErrType = ErrType::new_checked(1100, "Cancelled.");
+        assert_eq!(ErrType::t::<1100>("Cancelled."), ET);
+        assert!(std::panic::catch_unwind(|| ErrType::new_checked(4294, "")).is_err());
+    }
+    #[cfg(feature = "flag-constructors")]
+    #[test]
+    fn flag_constructors() {
+        assert_eq!(ErrType::t::<1000>("a"), ErrType::T1000("a"));
+        assert_eq!(ErrType::t::<4293>("b"), ErrType::T4293("b"));
+    }
 }
@@ -0,0 +1,58 @@
+//! Error types of common business errors.
+//!
+//! The flags are from 2000 to 2099; 2100 to 2999 are left to the business
+//! types of applications. Each type maps to an HTTP status; a status maps
+//! back to the [`ety_grpc`](super::ety_grpc) types.
+
+use super::{ErrType, status_map::StatusMapping};
+
+pub const VALIDATION_FAILED: ErrType =
+    ErrType::t::<2000>("The request failed validation.").with_name("VALIDATION_FAILED");
+pub const QUOTA_EXCEEDED: ErrType = ErrType::t::<2001>("The quota has been exceeded.").with_name("QUOTA_EXCEEDED");
+pub const PAYMENT_REQUIRED: ErrType =
+    ErrType::t::<2002>("Payment is required to continue.").with_name("PAYMENT_REQUIRED");
+pub const INSUFFICIENT_BALANCE: ErrType =
+    ErrType::t::<2003>("The balance is insufficient.").with_name("INSUFFICIENT_BALANCE");
+pub const VERSION_CONFLICT: ErrType =
+    ErrType::t::<2004>("The resource was modified by another request.").with_name("VERSION_CONFLICT");
+pub const DUPLICATE_REQUEST: ErrType =
+    ErrType::t::<2005>("The request has already been processed.").with_name("DUPLICATE_REQUEST");
+pub const INVALID_STATE: ErrType =
+    ErrType::t::<2006>("The operation is not allowed in the current state.").with_name("INVALID_STATE");
+pub const ACCOUNT_LOCKED: ErrType = ErrType::t::<2007>("The account is locked.").with_name("ACCOUNT_LOCKED");
+pub const ACCOUNT_DISABLED: ErrType = ErrType::t::<2008>("The account is disabled.").with_name("ACCOUNT_DISABLED");
+
+/// The mappings of these types to an HTTP status, see
+/// [`status_map`](super::status_map).
+pub const STATUS_MAPPINGS: &[StatusMapping] = &[
+    StatusMapping::to_status(VALIDATION_FAILED, 422),
+    StatusMapping::to_status(QUOTA_EXCEEDED, 429),
+    StatusMapping::to_status(PAYMENT_REQUIRED, 402),
+    StatusMapping::to_status(INSUFFICIENT_BALANCE, 402),
+    StatusMapping::to_status(VERSION_CONFLICT, 409),
+    StatusMapping::to_status(DUPLICATE_REQUEST, 409),
+    StatusMapping::to_status(INVALID_STATE, 409),
+    StatusMapping::to_status(ACCOUNT_LOCKED, 423),
+    StatusMapping::to_status(ACCOUNT_DISABLED, 403),
+];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use http::StatusCode;
+
+    use super::{ACCOUNT_LOCKED, STATUS_MAPPINGS, VERSION_CONFLICT};
+
+    #[test]
+    fn mappings() {
+        let mut flags = HashSet::new();
+        for mapping in STATUS_MAPPINGS {
+            assert!((2000..=2099).contains(&mapping.err_type.flag()));
+            assert!(flags.insert(mapping.err_type.flag()));
+            assert_eq!(mapping.status_code(), StatusCode::from(mapping.err_type));
+        }
+        assert_eq!(StatusCode::LOCKED, StatusCode::from(ACCOUNT_LOCKED));
+        assert_eq!(StatusCode::CONFLICT, StatusCode::from(VERSION_CONFLICT));
+    }
+}
@@ -2,69 +2,137 @@
 
 use http::StatusCode;
 
-use super::ErrType;
+use super::{
+    ErrType,
+    status_map::{self, StatusMapping},
+};
 
-pub const CANCELLED: ErrType = ErrType::T1000("The operation was cancelled.");
-pub const UNKNOWN: ErrType = ErrType::T1001("Server internal exception or client-side parsing status error.");
-pub const INVALID_ARGUMENT: ErrType = ErrType::T1002("Invalid request argument.");
-pub const DEADLINE_EXCEEDED: ErrType = ErrType::T1003("No response received before Deadline expires.");
-pub const NOT_FOUND: ErrType = ErrType::T1004("Some requested entity was not found.");
-pub const ALREADY_EXISTS: ErrType = ErrType::T1005("The entity that is attempting to be created already exists.");
-pub const PERMISSION_DENIED: ErrType = ErrType::T1006("No permission to execute the request.");
-pub const RESOURCE_EXHAUSTED: ErrType = ErrType::T1007("Insufficient memory or message size exceeds the limit.");
-pub const FAILED_PRECONDITION: ErrType = ErrType::T1008("Operation rejected, system not in required state.");
-pub const ABORTED: ErrType = ErrType::T1009("Operation aborted due to concurrency issues");
-pub const OUT_OF_RANGE: ErrType = ErrType::T1010("The operation was attempted past the valid range.");
-pub const UNIMPLEMENTED: ErrType = ErrType::T1011("The received request/response is not supported.");
-pub const INTERNAL: ErrType = ErrType::T1012("Internal errors indicate broken invariants.");
-pub const UNAVAILABLE: ErrType = ErrType::T1013("The service is currently unavailable or there is a connection error.");
-pub const DATA_LOSS: ErrType = ErrType::T1014("Unrecoverable data loss or corruption.");
+pub const CANCELLED: ErrType = ErrType::t::<1000>("The operation was cancelled.").with_name("CANCELLED");
+pub const UNKNOWN: ErrType =
+    ErrType::t::<1001>("Server internal exception or client-side parsing status error.").with_name("UNKNOWN");
+pub const INVALID_ARGUMENT: ErrType = ErrType::t::<1002>("Invalid request argument.").with_name("INVALID_ARGUMENT");
+pub const DEADLINE_EXCEEDED: ErrType =
+    ErrType::t::<1003>("No response received before Deadline expires.").with_name("DEADLINE_EXCEEDED");
+pub const NOT_FOUND: ErrType = ErrType::t::<1004>("Some requested entity was not found.").with_name("NOT_FOUND");
+pub const ALREADY_EXISTS: ErrType =
+    ErrType::t::<1005>("The entity that is attempting to be created already exists.").with_name("ALREADY_EXISTS");
+pub const PERMISSION_DENIED: ErrType =
+    ErrType::t::<1006>("No permission to execute the request.").with_name("PERMISSION_DENIED");
+pub const RESOURCE_EXHAUSTED: ErrType =
+    ErrType::t::<1007>("Insufficient memory or message size exceeds the limit.").with_name("RESOURCE_EXHAUSTED");
+pub const FAILED_PRECONDITION: ErrType =
+    ErrType::t::<1008>("Operation rejected, system not in required state.").with_name("FAILED_PRECONDITION");
+pub const ABORTED: ErrType = ErrType::t::<1009>("Operation aborted due to concurrency issues").with_name("ABORTED");
+pub const OUT_OF_RANGE: ErrType =
+    ErrType::t::<1010>("The operation was attempted past the valid range.").with_name("OUT_OF_RANGE");
+pub const UNIMPLEMENTED: ErrType =
+    ErrType::t::<1011>("The received request/response is not supported.").with_name("UNIMPLEMENTED");
+pub const INTERNAL: ErrType = ErrType::t::<1012>("Internal errors indicate broken invariants.").with_name("INTERNAL");
+pub const UNAVAILABLE: ErrType =
+    ErrType::t::<1013>("The service is currently unavailable or there is a connection error.").with_name("UNAVAILABLE");
+pub const DATA_LOSS: ErrType = ErrType::t::<1014>("Unrecoverable data loss or corruption.").with_name("DATA_LOSS");
 pub const UNAUTHENTICATED: ErrType =
-    ErrType::T1015("The request does not have valid authentication credentials for the operation.");
+    ErrType::t::<1015>("The request does not have valid authentication credentials for the operation.")
+        .with_name("UNAUTHENTICATED");
+
+/// The gRPC status codes 1 to 16, in the order of their flags.
+const GRPC_TYPES: [ErrType; 16] = [
+    CANCELLED,
+    UNKNOWN,
+    INVALID_ARGUMENT,
+    DEADLINE_EXCEEDED,
+    NOT_FOUND,
+    ALREADY_EXISTS,
+    PERMISSION_DENIED,
+    RESOURCE_EXHAUSTED,
+    FAILED_PRECONDITION,
+    ABORTED,
+    OUT_OF_RANGE,
+    UNIMPLEMENTED,
+    INTERNAL,
+    UNAVAILABLE,
+    DATA_LOSS,
+    UNAUTHENTICATED,
+];
+
+/// Returns the name and number of the gRPC status code equivalent to the
+/// `ErrType`.
+///
+/// Types defined outside of this module are mapped through their HTTP status.
+pub fn grpc_status(err_type: ErrType) -> (&'static str, u8) {
+    let mut flag = err_type.flag();
+    if !(CANCELLED.flag()..=UNAUTHENTICATED.flag()).contains(&flag) {
+        flag = ErrType::from(StatusCode::from(err_type)).flag();
+    }
+    grpc_status_of_flag(flag)
+}
+
+/// The gRPC status of a flag of this module, `INTERNAL` for any other flag.
+fn grpc_status_of_flag(flag: u16) -> (&'static str, u8) {
+    let idx = flag.saturating_sub(CANCELLED.flag());
+    match (GRPC_TYPES.get(usize::from(idx)), u8::try_from(idx)) {
+        (Some(err_type), Ok(code)) => (err_type.name(), code.saturating_add(1)),
+        _ => ("INTERNAL", 13),
+    }
+}
+
+/// The built-in mappings between these types and HTTP status codes, see
+/// [`status_map`].
+pub const STATUS_MAPPINGS: &[StatusMapping] = &[
+    StatusMapping::new(CANCELLED, 499),
+    StatusMapping::new(UNKNOWN, 520),
+    StatusMapping::new(INVALID_ARGUMENT, 400),
+    StatusMapping::new(DEADLINE_EXCEEDED, 504),
+    StatusMapping::new(NOT_FOUND, 404),
+    StatusMapping::new(ALREADY_EXISTS, 409),
+    StatusMapping::new(PERMISSION_DENIED, 403),
+    StatusMapping::new(RESOURCE_EXHAUSTED, 429),
+    StatusMapping::new(FAILED_PRECONDITION, 412),
+    StatusMapping::to_status(ABORTED, 409),
+    StatusMapping::new(OUT_OF_RANGE, 416),
+    StatusMapping::new(UNIMPLEMENTED, 501),
+    StatusMapping::new(INTERNAL, 500),
+    StatusMapping::new(UNAVAILABLE, 503),
+    StatusMapping::new(DATA_LOSS, 410),
+    StatusMapping::new(UNAUTHENTICATED, 401),
+    // Statuses without a type of their own.
+    StatusMapping::from_status(UNIMPLEMENTED, 405),
+    StatusMapping::from_status(DEADLINE_EXCEEDED, 408),
+    StatusMapping::from_status(RESOURCE_EXHAUSTED, 413),
+    StatusMapping::from_status(INVALID_ARGUMENT, 415),
+    StatusMapping::from_status(INVALID_ARGUMENT, 422),
+    StatusMapping::from_status(FAILED_PRECONDITION, 428),
+    StatusMapping::from_status(UNAVAILABLE, 502),
+    StatusMapping::from_status(UNIMPLEMENTED, 505),
+    StatusMapping::from_status(RESOURCE_EXHAUSTED, 507),
+];
 
 impl From<ErrType> for StatusCode {
+    #[inline]
     fn from(value: ErrType) -> Self {
-        match value.flag() {
-            v if v == CANCELLED.flag() => unsafe { StatusCode::from_u16(499).unwrap_unchecked() },
-            v if v == INVALID_ARGUMENT.flag() => StatusCode::BAD_REQUEST,
-            v if v == DEADLINE_EXCEEDED.flag() => StatusCode::GATEWAY_TIMEOUT,
-            v if v == NOT_FOUND.flag() => StatusCode::NOT_FOUND,
-            v if v == ALREADY_EXISTS.flag() => StatusCode::CONFLICT,
-            v if v == PERMISSION_DENIED.flag() => StatusCode::FORBIDDEN,
-            v if v == RESOURCE_EXHAUSTED.flag() => StatusCode::INSUFFICIENT_STORAGE,
-            v if v == FAILED_PRECONDITION.flag() => StatusCode::PRECONDITION_FAILED,
-            v if v == ABORTED.flag() => StatusCode::CONFLICT,
-            v if v == OUT_OF_RANGE.flag() => StatusCode::RANGE_NOT_SATISFIABLE,
-            v if v == UNIMPLEMENTED.flag() => StatusCode::NOT_IMPLEMENTED,
-            v if v == INTERNAL.flag() => StatusCode::INTERNAL_SERVER_ERROR,
-            v if v == UNAVAILABLE.flag() => StatusCode::SERVICE_UNAVAILABLE,
-            v if v == DATA_LOSS.flag() => StatusCode::GONE,
-            v if v == UNAUTHENTICATED.flag() => StatusCode::UNAUTHORIZED,
-            v if v == UNKNOWN.flag() => unsafe { StatusCode::from_u16(520).unwrap_unchecked() },
-            _ => StatusCode::INTERNAL_SERVER_ERROR,
-        }
+        status_map::http_status(value)
     }
 }
 
 impl From<StatusCode> for ErrType {
+    #[inline]
     fn from(value: StatusCode) -> Self {
-        match value.as_u16() {
-            499 => CANCELLED,
-            520 => UNKNOWN,
-            400 => INVALID_ARGUMENT,
-            504 => DEADLINE_EXCEEDED,
-            404 => NOT_FOUND,
-            409 => ALREADY_EXISTS,
-            403 => PERMISSION_DENIED,
-            507 => RESOURCE_EXHAUSTED,
-            412 => FAILED_PRECONDITION,
-            416 => OUT_OF_RANGE,
-            501 => UNIMPLEMENTED,
-            500 => INTERNAL,
-            503 => UNAVAILABLE,
-            410 => DATA_LOSS,
-            401 => UNAUTHENTICATED,
-            _ => INTERNAL,
+        status_map::err_type(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CANCELLED, INTERNAL, NOT_FOUND, UNAUTHENTICATED, grpc_status, grpc_status_of_flag};
+
+    #[test]
+    fn grpc_codes() {
+        assert_eq!(("CANCELLED", 1), grpc_status(CANCELLED));
+        assert_eq!(("NOT_FOUND", 5), grpc_status(NOT_FOUND));
+        assert_eq!(("UNAUTHENTICATED", 16), grpc_status(UNAUTHENTICATED));
+        assert_eq!(("INTERNAL", 13), grpc_status(INTERNAL));
+        for flag in [UNAUTHENTICATED.flag() + 1, 1300, 4293] {
+            assert_eq!(("INTERNAL", 13), grpc_status_of_flag(flag), "{flag}");
         }
     }
 }
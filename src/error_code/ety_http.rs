@@ -0,0 +1,88 @@
+//! Error types of the [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-15)
+//! 4xx and 5xx status codes, plus the widely used ones of RFC 6585 and
+//! RFC 7725.
+//!
+//! The flag of each type is `1000 + status`, i.e. from 1400 to 1511, e.g.
+//! `NOT_FOUND` is `T1404`. Each type maps to its status; a status maps back
+//! to the [`ety_grpc`](super::ety_grpc) types, use [`from_status`] for the
+//! types of this module.
+
+use http::StatusCode;
+
+use super::{ErrType, status_map::StatusMapping};
+
+macro_rules! http_types {
+    ($($status:literal $name:ident $text:literal;)*) => {
+        $(
+            pub const $name: ErrType =
+                ErrType::t::<{ 1000 + $status }>($text).with_name(concat!("HTTP_", stringify!($name)));
+        )*
+
+        /// The mappings of these types to their HTTP status, see
+        /// [`status_map`](super::status_map).
+        pub const STATUS_MAPPINGS: &[StatusMapping] = &[$(StatusMapping::to_status($name, $status)),*];
+    };
+}
+
+http_types! {
+    400 BAD_REQUEST "Bad request.";
+    401 UNAUTHORIZED "Authentication is required.";
+    402 PAYMENT_REQUIRED "Payment required.";
+    403 FORBIDDEN "Access to the resource is forbidden.";
+    404 NOT_FOUND "The resource was not found.";
+    405 METHOD_NOT_ALLOWED "The method is not allowed for the resource.";
+    406 NOT_ACCEPTABLE "No acceptable representation of the resource.";
+    407 PROXY_AUTHENTICATION_REQUIRED "Proxy authentication is required.";
+    408 REQUEST_TIMEOUT "The request timed out.";
+    409 CONFLICT "The request conflicts with the current state of the resource.";
+    410 GONE "The resource is no longer available.";
+    411 LENGTH_REQUIRED "The request has no Content-Length.";
+    412 PRECONDITION_FAILED "A precondition of the request failed.";
+    413 CONTENT_TOO_LARGE "The request content is too large.";
+    414 URI_TOO_LONG "The request URI is too long.";
+    415 UNSUPPORTED_MEDIA_TYPE "The media type of the request content is not supported.";
+    416 RANGE_NOT_SATISFIABLE "The requested range cannot be satisfied.";
+    417 EXPECTATION_FAILED "The Expect header cannot be met.";
+    421 MISDIRECTED_REQUEST "The request was sent to a server that cannot answer it.";
+    422 UNPROCESSABLE_CONTENT "The request content cannot be processed.";
+    426 UPGRADE_REQUIRED "The request requires a protocol upgrade.";
+    428 PRECONDITION_REQUIRED "The request must be conditional.";
+    429 TOO_MANY_REQUESTS "Too many requests.";
+    431 REQUEST_HEADER_FIELDS_TOO_LARGE "The request header fields are too large.";
+    451 UNAVAILABLE_FOR_LEGAL_REASONS "The resource is unavailable for legal reasons.";
+    500 INTERNAL_SERVER_ERROR "Internal server error.";
+    501 NOT_IMPLEMENTED "The functionality is not implemented.";
+    502 BAD_GATEWAY "Invalid response from an upstream server.";
+    503 SERVICE_UNAVAILABLE "The service is temporarily unavailable.";
+    504 GATEWAY_TIMEOUT "No timely response from an upstream server.";
+    505 HTTP_VERSION_NOT_SUPPORTED "The HTTP version is not supported.";
+}
+
+/// The type of this module of an HTTP status.
+pub fn from_status(status: StatusCode) -> Option<ErrType> {
+    STATUS_MAPPINGS
+        .iter()
+        .find(|v| v.http_status == status.as_u16())
+        .map(|v| v.err_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use super::{STATUS_MAPPINGS, from_status};
+    use crate::error_code::{ErrType, ety_grpc};
+
+    #[test]
+    fn mappings() {
+        for mapping in STATUS_MAPPINGS {
+            let err_type = mapping.err_type;
+            assert_eq!(1000 + mapping.http_status, err_type.flag());
+            assert_eq!(mapping.status_code(), StatusCode::from(err_type));
+            assert_eq!(Some(err_type), from_status(mapping.status_code()));
+            assert!(err_type.name().starts_with("HTTP_"));
+        }
+        assert_eq!(None, from_status(StatusCode::OK));
+        assert_eq!(ety_grpc::NOT_FOUND, ErrType::from(StatusCode::NOT_FOUND));
+    }
+}
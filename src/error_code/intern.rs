@@ -0,0 +1,85 @@
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+/// Returns a `&'static str` equal to `text`.
+///
+/// Each distinct text is leaked only once and shared afterwards, so names and
+/// texts loaded at runtime (from config or a database) can be used in
+/// `ErrType` and the `ErrPath*` types without leaking on every load.
+pub fn intern(text: &str) -> &'static str {
+    static INTERNED: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    if text.is_empty() {
+        return "";
+    }
+    let mut interned = INTERNED
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(existing) = interned.get(text) {
+        return existing;
+    }
+    let leaked: &'static str = Box::leak(text.to_owned().into_boxed_str());
+    interned.insert(leaked);
+    leaked
+}
+
+/// Conversion into a `&'static str`, interning owned strings with [`intern`].
+pub trait IntoStaticStr {
+    fn into_static_str(self) -> &'static str;
+}
+
+impl IntoStaticStr for &'static str {
+    #[inline]
+    fn into_static_str(self) -> &'static str {
+        self
+    }
+}
+
+impl IntoStaticStr for String {
+    #[inline]
+    fn into_static_str(self) -> &'static str {
+        intern(&self)
+    }
+}
+
+impl IntoStaticStr for &String {
+    #[inline]
+    fn into_static_str(self) -> &'static str {
+        intern(self)
+    }
+}
+
+impl IntoStaticStr for Box<str> {
+    #[inline]
+    fn into_static_str(self) -> &'static str {
+        intern(&self)
+    }
+}
+
+impl IntoStaticStr for Cow<'static, str> {
+    #[inline]
+    fn into_static_str(self) -> &'static str {
+        match self {
+            Cow::Borrowed(text) => text,
+            Cow::Owned(text) => intern(&text),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::default_numeric_fallback)]
+mod tests {
+    use super::{IntoStaticStr, intern};
+
+    #[test]
+    fn dedup() {
+        let a = intern(&format!("user-{}", 1));
+        let b = format!("user-{}", 1).into_static_str();
+        assert_eq!("user-1", a);
+        assert!(std::ptr::eq(a, b));
+        assert!(!std::ptr::eq(a, intern("user-2")));
+    }
+}
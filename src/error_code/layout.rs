@@ -0,0 +1,277 @@
+use std::fmt;
+
+use super::{ErrDecl, ErrPath, ErrType};
+
+/// The layout of a numeric error code: a type flag followed by `LEVELS` path
+/// levels of `LEVEL_DIGITS` decimal digits each, i.e.
+/// `code = type_flag * 10^(LEVELS * LEVEL_DIGITS) + path`.
+///
+/// [`DefaultCodeLayout`] is the layout of `ErrDecl::new`. Other layouts, e.g.
+/// four path levels or a 5-digit code, are declared as type aliases:
+///
+/// ```
+/// use api_response::error_code::CodeLayout;
+///
+/// type FourLevels = CodeLayout<4, 2, 1000, 1842>;
+/// assert_eq!(Ok(1004_01_02_03_04), FourLevels::encode(1004, [1, 2, 3, 4]));
+///
+/// type FiveDigits = CodeLayout<2, 2, 1, 9>;
+/// const NOT_FOUND: u64 = FiveDigits::new_code(4, [1, 20]);
+/// assert_eq!(40120, NOT_FOUND);
+/// assert!(FiveDigits::decode(100_000).is_err());
+/// ```
+///
+/// A layout whose largest code does not fit in a `u64` fails to compile when
+/// it is used.
+///
+/// A layout of three levels or more also declares `ErrDecl`s, whose codes are
+/// then used by the tally and the catalog:
+///
+/// ```
+/// use api_response::error_code::{CodeLayout, ErrDecl, ErrPath, ErrPathRoot, ety_grpc};
+///
+/// type FourLevels = CodeLayout<4, 2, 1000, 4293>;
+/// const EP: ErrPath = ErrPathRoot::x::<1>("product").y::<2>("system").z::<3>("user");
+/// const NOT_FOUND: ErrDecl = FourLevels::declare(ety_grpc::NOT_FOUND, EP, &[4]);
+/// assert_eq!(1004_01_02_03_04, NOT_FOUND.extract().code());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct CodeLayout<const LEVELS: usize, const LEVEL_DIGITS: u32, const TYPE_MIN: u64, const TYPE_MAX: u64>;
+
+/// The layout of `ErrDecl`: a type flag from 1000 to 4293 and three path
+/// levels of 0 to 99.
+pub type DefaultCodeLayout = CodeLayout<3, 2, 1000, 4293>;
+
+/// A code split into its type flag and path flags.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DecodedCode<const LEVELS: usize> {
+    pub type_flag: u64,
+    /// From the first (X) level to the last.
+    pub path: [u64; LEVELS],
+}
+
+impl<const LEVELS: usize, const LEVEL_DIGITS: u32, const TYPE_MIN: u64, const TYPE_MAX: u64>
+    CodeLayout<LEVELS, LEVEL_DIGITS, TYPE_MIN, TYPE_MAX>
+{
+    /// The exclusive upper bound of a path level flag.
+    pub const LEVEL_SPAN: u64 = 10u64.pow(LEVEL_DIGITS);
+    /// The exclusive upper bound of the path part of a code.
+    #[allow(clippy::cast_possible_truncation)]
+    pub const PATH_SPAN: u64 = 10u64.pow(LEVEL_DIGITS * LEVELS as u32);
+    /// The smallest valid code.
+    pub const MIN_CODE: u64 = TYPE_MIN * Self::PATH_SPAN;
+    /// The largest valid code.
+    pub const MAX_CODE: u64 = {
+        assert!(LEVELS > 0 && LEVEL_DIGITS > 0, "a layout needs at least one path digit");
+        assert!(TYPE_MIN > 0 && TYPE_MIN <= TYPE_MAX, "invalid type flag range");
+        match TYPE_MAX.checked_add(1) {
+            Some(end) => match end.checked_mul(Self::PATH_SPAN) {
+                Some(v) => v - 1,
+                None => panic!("the largest code of the layout does not fit in a u64"),
+            },
+            None => panic!("the largest code of the layout does not fit in a u64"),
+        }
+    };
+
+    /// Build a code, validating the type flag and every path flag.
+    pub const fn encode(type_flag: u64, path: [u64; LEVELS]) -> Result<u64, InvalidErrCode> {
+        let _ = Self::MAX_CODE;
+        if type_flag < TYPE_MIN || type_flag > TYPE_MAX {
+            return Err(InvalidErrCode::TypeFlag(type_flag));
+        }
+        let mut code = type_flag;
+        let mut level = 0;
+        while level < LEVELS {
+            let flag = path[level];
+            if flag >= Self::LEVEL_SPAN {
+                return Err(InvalidErrCode::PathFlag { level, flag });
+            }
+            code = code * Self::LEVEL_SPAN + flag;
+            level += 1;
+        }
+        Ok(code)
+    }
+
+    /// Build a code for use in consts.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a flag is out of range, which fails the build when
+    /// evaluated in a const context.
+    pub const fn new_code(type_flag: u64, path: [u64; LEVELS]) -> u64 {
+        match Self::encode(type_flag, path) {
+            Ok(code) => code,
+            Err(InvalidErrCode::TypeFlag(_)) => panic!("type flag out of range"),
+            Err(_) => panic!("path flag out of range"),
+        }
+    }
+
+    /// Split a code into its type flag and path flags.
+    pub const fn decode(code: u64) -> Result<DecodedCode<LEVELS>, InvalidErrCode> {
+        if code < Self::MIN_CODE || code > Self::MAX_CODE {
+            return Err(InvalidErrCode::Code(code));
+        }
+        let mut path = [0; LEVELS];
+        let mut rest = code;
+        let mut level = LEVELS;
+        while level > 0 {
+            level -= 1;
+            path[level] = rest % Self::LEVEL_SPAN;
+            rest /= Self::LEVEL_SPAN;
+        }
+        Ok(DecodedCode { type_flag: rest, path })
+    }
+
+    #[inline]
+    pub const fn validate(code: u64) -> bool {
+        Self::decode(code).is_ok()
+    }
+
+    /// Declare an error with the code of this layout: the X, Y and Z flags of
+    /// the path are its first three levels, followed by the flags of
+    /// `sub_path`.
+    pub const fn try_declare(
+        err_type: ErrType,
+        err_path: ErrPath,
+        sub_path: &[u64],
+    ) -> Result<ErrDecl, InvalidErrCode> {
+        if LEVELS < 3 || sub_path.len() != LEVELS - 3 {
+            return Err(InvalidErrCode::Levels {
+                expected: LEVELS,
+                found: 3 + sub_path.len(),
+            });
+        }
+        let xyz = err_path.flags();
+        let mut path = [0; LEVELS];
+        let mut level = 0;
+        while level < LEVELS {
+            path[level] = if level < 3 { xyz[level] } else { sub_path[level - 3] };
+            level += 1;
+        }
+        match Self::encode(err_type.flag() as u64, path) {
+            Ok(code) => Ok(ErrDecl::with_code(err_type, err_path, code)),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// [`try_declare`](Self::try_declare) for use in consts.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the levels do not match the layout or a flag is out of
+    /// range, which fails the build when evaluated in a const context.
+    pub const fn declare(err_type: ErrType, err_path: ErrPath, sub_path: &[u64]) -> ErrDecl {
+        match Self::try_declare(err_type, err_path, sub_path) {
+            Ok(err_decl) => err_decl,
+            Err(InvalidErrCode::Levels { .. }) => panic!("the path levels do not match the layout"),
+            Err(InvalidErrCode::TypeFlag(_)) => panic!("type flag out of range"),
+            Err(_) => panic!("path flag out of range"),
+        }
+    }
+}
+
+/// A possible error value when encoding or decoding a code with a
+/// `CodeLayout`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvalidErrCode {
+    TypeFlag(u64),
+    PathFlag {
+        level: usize,
+        flag: u64,
+    },
+    Code(u64),
+    /// The number of path levels of a declaration does not match the layout.
+    Levels {
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl fmt::Display for InvalidErrCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TypeFlag(flag) => write!(f, "invalid error type flag {flag}"),
+            Self::PathFlag { level, flag } => write!(f, "invalid error path flag {flag} at level {level}"),
+            Self::Code(code) => write!(f, "invalid error code {code}"),
+            Self::Levels { expected, found } => write!(f, "expected {expected} path levels, found {found}"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidErrCode {}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{CodeLayout, DefaultCodeLayout, InvalidErrCode};
+    use crate::error_code::{ErrDecl, ErrPath, ErrPathRoot, ety_grpc, tally::ErrDeclTally};
+
+    #[test]
+    fn default_layout() {
+        let err_decl = ety_grpc::NOT_FOUND.declare(ErrPathRoot::x::<1>("product").y::<2>("system").z::<3>("user"));
+        let code = err_decl.extract().code();
+        assert_eq!(Ok(code), DefaultCodeLayout::encode(1004, [1, 2, 3]));
+        let decoded = DefaultCodeLayout::decode(code).unwrap();
+        assert_eq!(1004, decoded.type_flag);
+        assert_eq!([1, 2, 3], decoded.path);
+        assert_eq!(1_000_000_000, DefaultCodeLayout::MIN_CODE);
+        assert_eq!(4_293_999_999, DefaultCodeLayout::MAX_CODE);
+        assert!(!DefaultCodeLayout::validate(999_999_999));
+        assert_eq!(
+            Err(InvalidErrCode::TypeFlag(999)),
+            DefaultCodeLayout::encode(999, [0, 0, 0])
+        );
+        assert_eq!(
+            Err(InvalidErrCode::PathFlag { level: 2, flag: 100 }),
+            DefaultCodeLayout::encode(1000, [0, 0, 100])
+        );
+    }
+
+    #[test]
+    fn custom_layouts() {
+        type FourLevels = CodeLayout<4, 2, 1000, 9999>;
+        const CODE: u64 = FourLevels::new_code(1004, [1, 2, 3, 4]);
+        assert_eq!(100_401_020_304, CODE);
+        assert_eq!([1, 2, 3, 4], FourLevels::decode(CODE).unwrap().path);
+
+        type ThreeDigitLevels = CodeLayout<2, 3, 1, 99>;
+        assert_eq!(Ok(99_999_001), ThreeDigitLevels::encode(99, [999, 1]));
+        assert_eq!(
+            Err(InvalidErrCode::Code(100_000_000)),
+            ThreeDigitLevels::decode(100_000_000)
+        );
+    }
+
+    #[test]
+    fn declare() {
+        type FourLevels = CodeLayout<4, 2, 1000, 4293>;
+        const EP: ErrPath = ErrPathRoot::x::<1>("product").y::<2>("system").z::<3>("user");
+        const NOT_FOUND: ErrDecl = FourLevels::declare(ety_grpc::NOT_FOUND, EP, &[4]);
+        assert_eq!(100_401_020_304, NOT_FOUND.code());
+        assert_eq!(
+            Err(InvalidErrCode::Levels { expected: 4, found: 3 }),
+            FourLevels::try_declare(ety_grpc::NOT_FOUND, EP, &[])
+        );
+        assert_eq!(
+            Err(InvalidErrCode::PathFlag { level: 3, flag: 100 }),
+            FourLevels::try_declare(ety_grpc::NOT_FOUND, EP, &[100])
+        );
+        assert_eq!(
+            Ok(ety_grpc::NOT_FOUND.declare(EP)),
+            DefaultCodeLayout::try_declare(ety_grpc::NOT_FOUND, EP, &[])
+        );
+
+        let api_error = NOT_FOUND.api_error();
+        assert_eq!(100_401_020_304, api_error.code());
+        assert_eq!(Some(ety_grpc::NOT_FOUND.flag()), api_error.err_type().map(|v| v.flag()));
+
+        let tally = ErrDeclTally::from_iter([NOT_FOUND, FourLevels::declare(ety_grpc::NOT_FOUND, EP, &[5])]);
+        assert_eq!(2, tally.unique().len());
+        let catalog = tally.catalog();
+        assert_eq!(100_401_020_304, catalog.entries[0].code);
+        assert_eq!(Ok(NOT_FOUND), catalog.entries[0].err_decl().map_err(|e| e.to_string()));
+    }
+}
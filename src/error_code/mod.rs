@@ -1,6 +1,14 @@
+pub mod catalog;
+mod class;
+pub mod counter;
 mod errpath;
 mod errtype;
+pub mod ety_biz;
 pub mod ety_grpc;
+pub mod ety_http;
+mod intern;
+mod layout;
+pub mod status_map;
 pub mod tally;
 
 use std::{
@@ -9,24 +17,49 @@ use std::{
     thread::LocalKey,
 };
 
+pub use class::*;
 pub use errpath::*;
 pub use errtype::*;
 use getset2::Getset2;
+pub use intern::{IntoStaticStr, intern};
+pub use layout::*;
 use serde::{Deserialize, Serialize};
 
-use crate::ApiError;
+use crate::{ApiError, ErrCode};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, PartialOrd, Ord, Hash, Serialize)]
 #[non_exhaustive]
 pub struct ErrDecl {
     pub err_type: ErrType,
     pub err_path: ErrPath,
+    /// The code in the layout the declaration was made with, see
+    /// [`CodeLayout::declare`].
+    #[serde(skip)]
+    code: u64,
 }
 
 impl ErrDecl {
+    /// Declare an error with the code of [`DefaultCodeLayout`].
     #[inline]
     pub const fn new(err_type: ErrType, err_path: ErrPath) -> Self {
-        Self { err_type, err_path }
+        Self {
+            err_type,
+            err_path,
+            code: DefaultCodeLayout::new_code(err_type.flag() as u64, err_path.flags()),
+        }
+    }
+    #[inline]
+    pub(crate) const fn with_code(err_type: ErrType, err_path: ErrPath, code: u64) -> Self {
+        Self {
+            err_type,
+            err_path,
+            code,
+        }
+    }
+    /// The code of the declaration.
+    #[inline]
+    pub const fn code(&self) -> u64 {
+        self.code
     }
     #[inline]
     pub const fn err_flag(&self) -> u32 {
@@ -48,11 +81,39 @@ impl ErrDecl {
     }
     #[inline(always)]
     pub const fn extract(&self) -> ErrBrief {
-        ErrBrief::new(self.err_type, &self.err_path)
+        ErrBrief {
+            message: self.err_type.text(),
+            code: self.code,
+        }
+    }
+    /// The symbolic code: the upper-cased path names and the name of the
+    /// type joined with `_`, e.g. `PRODUCT_SYSTEM_MODULE_NOT_FOUND`.
+    pub fn symbol(&self) -> String {
+        let parent = self.err_path.parent();
+        let mut symbol = String::new();
+        for name in [parent.root().name(), parent.name(), self.err_path.name()] {
+            push_symbol_part(&mut symbol, name);
+        }
+        push_symbol_part(&mut symbol, &self.err_type.symbol_name());
+        symbol
     }
+    /// An `ApiError` carrying both the numeric and the symbolic code.
     #[inline(always)]
     pub fn api_error(&self) -> ApiError {
-        self.extract().api_error()
+        self.extract().api_error().with_symbol(self.symbol())
+    }
+}
+
+/// Append the upper-cased alphanumeric words of `name`, separated by `_`.
+fn push_symbol_part(symbol: &mut String, name: &str) {
+    for word in name
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|v| !v.is_empty())
+    {
+        if !symbol.is_empty() {
+            symbol.push('_');
+        }
+        symbol.extend(word.chars().map(|c| c.to_ascii_uppercase()));
     }
 }
 
@@ -67,8 +128,9 @@ impl Display for ErrDecl {
 #[non_exhaustive]
 pub struct ErrBrief {
     message: &'static str,
-    /// The value range of the code is from 1000000000 to 4293999999 inclusive.
-    code: u32,
+    /// The value range of the code is from 1000000000 to 4293999999 inclusive
+    /// with [`DefaultCodeLayout`].
+    code: u64,
 }
 impl Display for ErrBrief {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -80,17 +142,15 @@ impl Display for ErrBrief {
     }
 }
 impl ErrBrief {
+    /// The brief of the error declared with [`DefaultCodeLayout`].
     #[inline(always)]
     pub const fn new(err_type: ErrType, err_path: &ErrPath) -> Self {
-        Self {
-            message: err_type.text(),
-            code: (err_type.flag() as u32 * 1000000) + err_path.path_flag(),
-        }
+        ErrDecl::new(err_type, *err_path).extract()
     }
     #[inline(always)]
     pub fn api_error(&self) -> ApiError {
         ApiError {
-            code: self.code,
+            code: ErrCode::new(self.code),
             message: self.message.to_owned(),
             details: None,
             source: None,
@@ -150,12 +210,15 @@ impl BitOr<&'static LocalKey<ErrPath>> for ErrType {
 }
 
 #[cfg(test)]
+#[allow(clippy::shadow_unrelated)]
+#[cfg_attr(not(feature = "flag-constructors"), allow(unused_imports))]
 mod tests {
     use std::cell::LazyCell;
 
     use super::{ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType};
     use crate::ApiError;
 
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn display() {
         const ET: ErrType = ErrType::T1100("The operation was cancelled.");
@@ -175,6 +238,7 @@ mod tests {
         assert_eq!(EC.api_error().code(), api_error.code());
     }
 
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn min_max_code() {
         let min_code: ErrDecl = ErrType::T1000("") + ErrPathRoot::X00("").Y00("").Z00("");
@@ -189,4 +253,18 @@ mod tests {
             max_code.to_string()
         );
     }
+
+    #[test]
+    fn t_xyz() {
+        const ET: ErrType = ErrType::t::<1100>("The operation was cancelled.");
+        const EP: ErrPath = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("module");
+        let api_error: ApiError = ET | &EP;
+        assert_eq!(1100000120, api_error.code());
+        assert_eq!(Some("PRODUCT_SYSTEM_MODULE_T1100"), api_error.symbol());
+
+        let min_code: ErrDecl = ErrType::t::<1000>("") + ErrPathRoot::x::<0>("").y::<0>("").z::<0>("");
+        assert_eq!(1000000000, min_code.code());
+        let max_code: ErrDecl = ErrType::t::<4293>("") + ErrPathRoot::x::<99>("").y::<99>("").z::<99>("");
+        assert_eq!(4293999999, max_code.code());
+    }
 }
@@ -0,0 +1,203 @@
+//! A registrable mapping table between `ErrType`s and HTTP status codes.
+//!
+//! `From<ErrType> for StatusCode` and `From<StatusCode> for ErrType` look the
+//! mapping up, in order, in the mappings registered at runtime (the latest
+//! first), the ones submitted with [`status_mapping!`](crate::status_mapping),
+//! and the built-in [`ety_grpc::STATUS_MAPPINGS`],
+//! [`ety_http::STATUS_MAPPINGS`] and [`ety_biz::STATUS_MAPPINGS`]. A type
+//! without a mapping is a 500; a status without a mapping falls back by its
+//! class: 4xx to `INVALID_ARGUMENT`, 5xx to `INTERNAL` and any other to
+//! `UNKNOWN`.
+//!
+//! ```
+//! use api_response::{error_code::ErrType, status_mapping};
+//! use http::StatusCode;
+//!
+//! const TOO_EARLY: ErrType = ErrType::t::<2100>("Too early.");
+//! status_mapping!(TOO_EARLY => 425);
+//!
+//! assert_eq!(StatusCode::TOO_EARLY, StatusCode::from(TOO_EARLY));
+//! ```
+
+use std::sync::{PoisonError, RwLock};
+
+use http::StatusCode;
+
+use super::{ErrType, ety_biz, ety_grpc, ety_http};
+
+/// Register a mapping between an `ErrType` and an HTTP status at link time.
+///
+/// `status_mapping!(ERR_TYPE => 429)` maps in both directions;
+/// `status_mapping!(ERR_TYPE => 429, to_status)` only maps the type to the
+/// status, for a type sharing its status with another one.
+#[macro_export]
+macro_rules! status_mapping {
+    ($err_type:expr => $http_status:expr) => {
+        $crate::error_code::tally::inventory::submit! {
+            $crate::error_code::status_map::StatusMapping::new($err_type, $http_status)
+        }
+    };
+    ($err_type:expr => $http_status:expr,to_status) => {
+        $crate::error_code::tally::inventory::submit! {
+            $crate::error_code::status_map::StatusMapping::to_status($err_type, $http_status)
+        }
+    };
+    ($err_type:expr => $http_status:expr,from_status) => {
+        $crate::error_code::tally::inventory::submit! {
+            $crate::error_code::status_map::StatusMapping::from_status($err_type, $http_status)
+        }
+    };
+}
+
+/// The directions a [`StatusMapping`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MappingDirection {
+    Both,
+    /// `ErrType` to HTTP status only.
+    ToStatus,
+    /// HTTP status to `ErrType` only.
+    FromStatus,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct StatusMapping {
+    pub err_type: ErrType,
+    pub http_status: u16,
+    pub direction: MappingDirection,
+}
+
+impl StatusMapping {
+    /// A mapping in both directions.
+    #[inline]
+    pub const fn new(err_type: ErrType, http_status: u16) -> Self {
+        Self {
+            err_type,
+            http_status,
+            direction: MappingDirection::Both,
+        }
+    }
+    #[inline]
+    pub const fn to_status(err_type: ErrType, http_status: u16) -> Self {
+        Self {
+            err_type,
+            http_status,
+            direction: MappingDirection::ToStatus,
+        }
+    }
+    #[inline]
+    pub const fn from_status(err_type: ErrType, http_status: u16) -> Self {
+        Self {
+            err_type,
+            http_status,
+            direction: MappingDirection::FromStatus,
+        }
+    }
+    /// The HTTP status, or 500 when `http_status` is not a valid status.
+    #[inline]
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::from_u16(self.http_status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+    const fn maps_to_status(&self, err_type: ErrType) -> bool {
+        !matches!(self.direction, MappingDirection::FromStatus) && self.err_type.flag() == err_type.flag()
+    }
+    const fn maps_from_status(&self, http_status: u16) -> bool {
+        !matches!(self.direction, MappingDirection::ToStatus) && self.http_status == http_status
+    }
+}
+
+inventory::collect!(StatusMapping);
+
+/// The mappings registered at runtime.
+static RUNTIME_STATUS_MAPPINGS: RwLock<Vec<StatusMapping>> = RwLock::new(Vec::new());
+
+/// Register a mapping at runtime; it takes precedence over the mappings
+/// registered before it.
+pub fn register_status_mapping(mapping: StatusMapping) {
+    RUNTIME_STATUS_MAPPINGS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push(mapping);
+}
+
+fn find_mapping(matches: impl Fn(&StatusMapping) -> bool) -> Option<StatusMapping> {
+    RUNTIME_STATUS_MAPPINGS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .rev()
+        .find(|v| matches(v))
+        .or_else(|| inventory::iter::<StatusMapping>.into_iter().find(|v| matches(v)))
+        .or_else(|| {
+            [
+                ety_grpc::STATUS_MAPPINGS,
+                ety_http::STATUS_MAPPINGS,
+                ety_biz::STATUS_MAPPINGS,
+            ]
+            .into_iter()
+            .flatten()
+            .find(|v| matches(v))
+        })
+        .copied()
+}
+
+/// The HTTP status of the `ErrType`, see the [module](self) docs.
+pub fn http_status(err_type: ErrType) -> StatusCode {
+    find_mapping(|v| v.maps_to_status(err_type)).map_or(StatusCode::INTERNAL_SERVER_ERROR, |v| v.status_code())
+}
+
+/// The `ErrType` of the HTTP status, see the [module](self) docs.
+pub fn err_type(status: StatusCode) -> ErrType {
+    find_mapping(|v| v.maps_from_status(status.as_u16())).map_or_else(
+        || {
+            if status.is_client_error() {
+                ety_grpc::INVALID_ARGUMENT
+            } else if status.is_server_error() {
+                ety_grpc::INTERNAL
+            } else {
+                ety_grpc::UNKNOWN
+            }
+        },
+        |v| v.err_type,
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::error_code::{ErrType, ety_grpc};
+
+    #[test]
+    fn defaults() {
+        assert_eq!(
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::from(ety_grpc::RESOURCE_EXHAUSTED)
+        );
+        assert_eq!(StatusCode::CONFLICT, StatusCode::from(ety_grpc::ABORTED));
+        for (status, err_type) in [
+            (429, ety_grpc::RESOURCE_EXHAUSTED),
+            (507, ety_grpc::RESOURCE_EXHAUSTED),
+            (405, ety_grpc::UNIMPLEMENTED),
+            (408, ety_grpc::DEADLINE_EXCEEDED),
+            (422, ety_grpc::INVALID_ARGUMENT),
+            (502, ety_grpc::UNAVAILABLE),
+            (409, ety_grpc::ALREADY_EXISTS),
+            (418, ety_grpc::INVALID_ARGUMENT),
+            (599, ety_grpc::INTERNAL),
+            (302, ety_grpc::UNKNOWN),
+        ] {
+            assert_eq!(
+                err_type,
+                ErrType::from(StatusCode::from_u16(status).unwrap()),
+                "{status}"
+            );
+        }
+        assert_eq!(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            StatusCode::from(ErrType::t::<4000>(""))
+        );
+    }
+}
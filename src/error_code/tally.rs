@@ -1,13 +1,16 @@
 use std::{
     collections::{BTreeMap, HashSet},
+    fmt::Write,
+    sync::{PoisonError, RwLock},
     thread::LocalKey,
 };
 
+use http::StatusCode;
 // re-export
 pub use inventory;
 use serde::{Deserialize, Serialize};
 
-use super::{ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType};
+use super::{ErrClass, ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType, catalog::Catalog, ety_grpc};
 
 /// Quickly create an `ApiError` builder `ApiErr` and collect error code mode
 /// information.
@@ -17,32 +20,32 @@ macro_rules! api_err {
         $crate::error_code::tally::inventory::submit! {
             $err_decl
         }
-        $err_decl.api_error()
+        $crate::error_code::counter::record_evaluated($err_decl.api_error())
     }};
     ($err_type:expr, & $local_key_err_path:expr) => {{
         $crate::error_code::tally::inventory::submit! {
             $crate::error_code::tally::LocalKeyErrDecl::new($err_type, &$local_key_err_path)
         }
-        $err_type | &$local_key_err_path
+        $crate::error_code::counter::record_evaluated($err_type | &$local_key_err_path)
     }};
     ($err_type:expr, $new_text:expr, & $local_key_err_path:expr) => {{
         $crate::error_code::tally::inventory::submit! {
             $crate::error_code::tally::LocalKeyErrDecl::new($err_type.with_text($new_text), &$local_key_err_path)
         }
-        ($err_type | $new_text) | &$local_key_err_path
+        $crate::error_code::counter::record_evaluated(($err_type | $new_text) | &$local_key_err_path)
     }};
 
     ($err_type:expr, $err_path:expr) => {{
         $crate::error_code::tally::inventory::submit! {
             $err_type.declare($err_path)
         }
-        $err_type | &$err_path
+        $crate::error_code::counter::record_evaluated($err_type | &$err_path)
     }};
     ($err_type:expr, $new_text:expr, $err_path:expr) => {{
         $crate::error_code::tally::inventory::submit! {
             $err_type.with_text($new_text).declare($err_path)
         }
-        ($err_type | $new_text) | &$err_path
+        $crate::error_code::counter::record_evaluated(($err_type | $new_text) | &$err_path)
     }};
 }
 
@@ -60,6 +63,24 @@ impl LocalKeyErrDecl {
 inventory::collect!(ErrDecl);
 inventory::collect!(LocalKeyErrDecl);
 
+/// The declarations registered at runtime, e.g. loaded from config or a
+/// database.
+static RUNTIME_ERR_DECLS: RwLock<Vec<ErrDecl>> = RwLock::new(Vec::new());
+
+/// Register a declaration loaded at runtime, so it is tallied next to the
+/// `inventory`-collected ones.
+pub fn register_err_decl(err_decl: ErrDecl) {
+    register_err_decls([err_decl]);
+}
+
+/// Register declarations loaded at runtime, see [`register_err_decl`].
+pub fn register_err_decls(err_decls: impl IntoIterator<Item = ErrDecl>) {
+    RUNTIME_ERR_DECLS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .extend(err_decls);
+}
+
 /// Obtain the list of error code declaration.
 pub fn tally_err_decl() -> ErrDeclTally {
     let total = inventory::iter::<ErrDecl>
@@ -70,6 +91,13 @@ pub fn tally_err_decl() -> ErrDeclTally {
                 .into_iter()
                 .map(|v| v.err_type + v.err_path),
         )
+        .chain(
+            RUNTIME_ERR_DECLS
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .iter()
+                .copied(),
+        )
         .collect();
     ErrDeclTally { total }
 }
@@ -80,6 +108,14 @@ pub struct ErrDeclTally {
     total: Vec<ErrDecl>,
 }
 
+impl FromIterator<ErrDecl> for ErrDeclTally {
+    fn from_iter<I: IntoIterator<Item = ErrDecl>>(iter: I) -> Self {
+        Self {
+            total: iter.into_iter().collect(),
+        }
+    }
+}
+
 pub type ErrDeclTree =
     BTreeMap<ErrPathRoot, BTreeMap<ErrPathParent, BTreeMap<ErrPath, BTreeMap<ErrType, HashSet<ErrDecl>>>>>;
 
@@ -146,6 +182,10 @@ impl ErrDeclTally {
         }
         b_tree_map
     }
+    /// The versioned catalog of the unique declarations.
+    pub fn catalog(&self) -> Catalog {
+        Catalog::from_err_decls(&self.unique())
+    }
     pub fn json(&self) -> String {
         unsafe { serde_json::to_string_pretty(&self.text_tree()).unwrap_unchecked() }
     }
@@ -185,18 +225,163 @@ impl ErrDeclTally {
         self.text_vec().serialize(ser).unwrap();
         writer
     }
+    /// Render the catalog as Markdown, with one section per X/Y/Z path and
+    /// one table row per error code.
+    ///
+    /// The output is sorted, so it is stable across runs.
+    pub fn markdown(&self) -> String {
+        let mut md = String::from("# Error Codes\n");
+        for (root, parents) in self.tree() {
+            let _ = write!(md, "\n## X{:02} {}\n", root.flag(), escape_markdown(root.name()));
+            for (parent, paths) in parents {
+                let _ = write!(md, "\n### Y{:02} {}\n", parent.flag(), escape_markdown(parent.name()));
+                for (path, types) in paths {
+                    let _ = write!(md, "\n#### Z{:02} {}\n\n", path.flag(), escape_markdown(path.name()));
+                    md.push_str("| Code | Message | Type | HTTP Status | gRPC | Class |\n");
+                    md.push_str("| --- | --- | --- | --- | --- | --- |\n");
+                    for row in catalog_rows(types) {
+                        let _ = writeln!(
+                            md,
+                            "| {} | {} | {} | {} | {} | {} |",
+                            row.code,
+                            escape_markdown(row.message),
+                            escape_markdown(&row.err_type),
+                            row.http_status,
+                            row.grpc,
+                            row.class
+                        );
+                    }
+                }
+            }
+        }
+        md
+    }
+    /// Render the catalog as a self-contained HTML document, with one section
+    /// per X/Y/Z path and one table row per error code.
+    ///
+    /// The output is sorted, so it is stable across runs.
+    pub fn html(&self) -> String {
+        let mut html = String::from(concat!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n",
+            "<title>Error Codes</title>\n<style>\n",
+            "body{font-family:sans-serif;margin:2em}\n",
+            "table{border-collapse:collapse;margin-bottom:1em}\n",
+            "th,td{border:1px solid #ccc;padding:4px 8px;text-align:left}\n",
+            "th{background:#f4f4f4}\n",
+            "</style>\n</head>\n<body>\n<h1>Error Codes</h1>\n",
+        ));
+        for (root, parents) in self.tree() {
+            let _ = writeln!(html, "<h2>X{:02} {}</h2>", root.flag(), escape_html(root.name()));
+            for (parent, paths) in parents {
+                let _ = writeln!(html, "<h3>Y{:02} {}</h3>", parent.flag(), escape_html(parent.name()));
+                for (path, types) in paths {
+                    let _ = writeln!(html, "<h4>Z{:02} {}</h4>", path.flag(), escape_html(path.name()));
+                    html.push_str("<table>\n<tr><th>Code</th><th>Message</th><th>Type</th><th>HTTP Status</th><th>gRPC</th><th>Class</th></tr>\n");
+                    for row in catalog_rows(types) {
+                        let _ = writeln!(
+                            html,
+                            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                            row.code,
+                            escape_html(row.message),
+                            escape_html(&row.err_type),
+                            row.http_status,
+                            row.grpc,
+                            row.class
+                        );
+                    }
+                    html.push_str("</table>\n");
+                }
+            }
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// A row of the rendered error code catalog.
+struct CatalogRow {
+    code: u64,
+    message: &'static str,
+    err_type: String,
+    http_status: String,
+    grpc: String,
+    class: ErrClass,
+}
+
+/// Returns the rows of the declarations under one `ErrPath`, sorted by code
+/// and message.
+fn catalog_rows(types: BTreeMap<ErrType, HashSet<ErrDecl>>) -> Vec<CatalogRow> {
+    let mut rows: Vec<CatalogRow> = types
+        .into_values()
+        .flatten()
+        .map(|err_decl| {
+            let brief = err_decl.extract();
+            let err_type = err_decl.err_type();
+            let status = StatusCode::from(*err_type);
+            let (grpc_name, grpc_code) = ety_grpc::grpc_status(*err_type);
+            CatalogRow {
+                code: brief.code(),
+                message: brief.message(),
+                err_type: format!("T{:04}", err_type.flag()),
+                http_status: format!("{} {}", status.as_u16(), status.canonical_reason().unwrap_or_default())
+                    .trim_end()
+                    .to_owned(),
+                grpc: format!("{grpc_name} ({grpc_code})"),
+                class: err_type.class(),
+            }
+        })
+        .collect();
+    rows.sort_by(|a, b| (a.code, a.message).cmp(&(b.code, b.message)));
+    rows
+}
+
+/// Escape the Markdown metacharacters and the HTML `<` and `&`, and fold the
+/// line breaks, so that the text stays in its heading or table cell.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' | '`' | '*' | '_' | '#' | '|' | '[' | ']' | '(' | ')' | '!' | '~' | '>' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '<' => escaped.push_str("&lt;"),
+            '&' => escaped.push_str("&amp;"),
+            '\r' | '\n' => escaped.push(' '),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 #[cfg(test)]
+#[allow(clippy::missing_const_for_thread_local)]
+#[cfg_attr(not(feature = "flag-constructors"), allow(unused_imports))]
 mod tests {
     use crate::{
         ApiError,
         error_code::{
-            ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType,
+            ErrDecl, ErrPath, ErrPathParent, ErrPathRoot, ErrType, ety_grpc,
             tally::{ErrDeclTally, tally_err_decl},
         },
     };
 
+    #[cfg(feature = "flag-constructors")]
     #[test]
     fn macro_api_err() {
         const ET: ErrType = ErrType::T1100("The operation was cancelled.");
@@ -231,4 +416,43 @@ mod tests {
         println!("{}", tally.json());
         println!("{}", tally.xml());
     }
+
+    #[test]
+    fn markdown_and_html() {
+        const EP: ErrPath = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        let tally = ErrDeclTally {
+            total: vec![
+                ety_grpc::NOT_FOUND.declare(EP),
+                (ety_grpc::INVALID_ARGUMENT | "Invalid <email> | name.").declare(EP),
+                ety_grpc::NOT_FOUND.declare(EP),
+                ety_grpc::UNAUTHENTICATED.declare(ErrPathRoot::x::<0>("product").y::<0>("auth").z::<1>("token")),
+            ],
+        };
+
+        let md = tally.markdown();
+        assert_eq!(md, tally.markdown());
+        assert!(md.contains("## X00 product\n\n### Y00 auth\n\n#### Z01 token\n"));
+        assert!(md.contains(
+            "| 1004000120 | Some requested entity was not found. | T1004 | 404 Not Found | NOT_FOUND (5) | client, info |\n"
+        ));
+        assert!(md.contains(
+            "| 1002000120 | Invalid &lt;email\\> \\| name. | T1002 | 400 Bad Request | INVALID_ARGUMENT (3) | client, info |"
+        ));
+        assert!(md.find("Y00 auth") < md.find("Y01 system"));
+        assert_eq!(3, md.matches("| 10").count());
+        let multiline = ErrDeclTally {
+            total: vec![ety_grpc::NOT_FOUND.declare(ErrPathRoot::x::<1>("a\nb").y::<0>("c|d").z::<0>("*e_ `#1`"))],
+        };
+        assert!(
+            multiline
+                .markdown()
+                .contains("## X01 a b\n\n### Y00 c\\|d\n\n#### Z00 \\*e\\_ \\`\\#1\\`\n")
+        );
+
+        let html = tally.html();
+        assert_eq!(html, tally.html());
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<td>Invalid &lt;email&gt; | name.</td>"));
+        assert!(html.contains("<td>401 Unauthorized</td><td>UNAUTHENTICATED (16)</td><td>client, warn</td>"));
+    }
 }
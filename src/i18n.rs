@@ -0,0 +1,284 @@
+//! Localized error messages.
+//!
+//! A [`MessageBundle`] maps an error code, a symbolic code, or an `ErrType`
+//! flag such as `T1004`, to the message of one locale. A [`Localizer`] picks
+//! the bundle from the locale in the meta or from an `Accept-Language` header,
+//! fills the `{placeholder}`s of the message from the error details, and keeps
+//! the default text when no message is found.
+//!
+//! With the `tokio` feature, the `salvo` and `axum` integrations localize the
+//! error responses they render with the localizer of
+//! `meta_context::scope_localizer`, which the salvo `Localize` hoop scopes
+//! with the `Accept-Language` header of the request. Otherwise, call
+//! [`Localizer::localize_response`] before the response is returned.
+
+use std::collections::HashMap;
+
+use crate::{ApiError, ApiResponse, ErrCode, ErrorResponse, ResponseMeta, error_code::ErrType};
+
+/// The messages of one locale.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct MessageBundle {
+    locale: String,
+    messages: HashMap<String, String>,
+}
+
+impl MessageBundle {
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            messages: HashMap::new(),
+        }
+    }
+    /// Load a JSON object keyed by error code (`"1004000120"`), symbolic code
+    /// (`"USER_NOT_FOUND"`) or `ErrType` flag (`"T1004"`).
+    pub fn from_json(locale: impl Into<String>, json: &str) -> Result<Self, serde_json::Error> {
+        Ok(Self {
+            locale: locale.into(),
+            messages: serde_json::from_str(json)?,
+        })
+    }
+    pub fn with_code(mut self, code: impl Into<ErrCode>, message: impl Into<String>) -> Self {
+        self.messages.insert(code.into().to_string(), message.into());
+        self
+    }
+    pub fn with_type(mut self, err_type: ErrType, message: impl Into<String>) -> Self {
+        self.messages.insert(type_key(err_type.flag()), message.into());
+        self
+    }
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+    /// The message of the numeric code, then of the symbolic code, falling
+    /// back to the message of its `ErrType`.
+    pub fn message(&self, code: &ErrCode) -> Option<&str> {
+        let numeric = code.numeric();
+        (numeric != 0)
+            .then(|| self.messages.get(&numeric.to_string()))
+            .flatten()
+            .or_else(|| self.messages.get(code.symbol()?))
+            .or_else(|| self.messages.get(&type_key(code.err_type()?.flag())))
+            .map(String::as_str)
+    }
+}
+
+fn type_key(flag: u16) -> String {
+    format!("T{flag:04}")
+}
+
+/// A set of bundles, one per locale.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct Localizer {
+    bundles: Vec<MessageBundle>,
+}
+
+impl Localizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Add a bundle; the messages of a bundle with the same locale are merged.
+    pub fn with_bundle(mut self, bundle: MessageBundle) -> Self {
+        self.add_bundle(bundle);
+        self
+    }
+    pub fn add_bundle(&mut self, bundle: MessageBundle) -> &mut Self {
+        match self
+            .bundles
+            .iter_mut()
+            .find(|v| v.locale.eq_ignore_ascii_case(&bundle.locale))
+        {
+            Some(existing) => existing.messages.extend(bundle.messages),
+            None => self.bundles.push(bundle),
+        }
+        self
+    }
+    /// The bundle of the locale, falling back to its parent locales
+    /// (`zh-Hans-CN`, `zh-Hans`, `zh`).
+    pub fn bundle(&self, locale: &str) -> Option<&MessageBundle> {
+        let mut tag = locale.trim();
+        loop {
+            if let Some(bundle) = self.bundles.iter().find(|v| v.locale.eq_ignore_ascii_case(tag)) {
+                return Some(bundle);
+            }
+            tag = tag.rsplit_once('-')?.0;
+        }
+    }
+    /// The first locale of an `Accept-Language` header, by quality, that has
+    /// a bundle.
+    pub fn negotiate<'a>(&'a self, accept_language: &str) -> Option<&'a str> {
+        let mut ranges: Vec<(&str, f32)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|v| v.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |v| v.trim().parse::<f32>().ok())?;
+                (!tag.is_empty() && tag != "*" && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranges
+            .into_iter()
+            .find_map(|(tag, _)| self.bundle(tag))
+            .map(MessageBundle::locale)
+    }
+    /// The localized message of the error, with `{placeholder}`s filled from
+    /// its details.
+    pub fn message(&self, error: &ApiError, locale: &str) -> Option<String> {
+        let template = self.bundle(locale)?.message(error.err_code())?;
+        Some(fill_placeholders(template, error.details()))
+    }
+    /// Replace the message of the error, keeping the default text when there
+    /// is no message for the locale. Returns whether the message was replaced.
+    pub fn localize_error(&self, error: &mut ApiError, locale: &str) -> bool {
+        match self.message(error, locale) {
+            Some(message) => {
+                error.message = message;
+                true
+            }
+            None => false,
+        }
+    }
+    /// Localize an error response with the [`locale`](ResponseMeta::locale)
+    /// of its meta, or else the best match of `accept_language`, and record
+    /// the locale used in the meta.
+    pub fn localize_response<Data, Meta: ResponseMeta>(
+        &self,
+        response: &mut ApiResponse<Data, Meta>,
+        accept_language: Option<&str>,
+    ) {
+        if let ApiResponse::Error(error_response) = response {
+            self.localize_error_response(error_response, accept_language);
+        }
+    }
+    /// Localize an error response, see
+    /// [`localize_response`](Self::localize_response).
+    pub fn localize_error_response<Meta: ResponseMeta>(
+        &self,
+        error_response: &mut ErrorResponse<Meta>,
+        accept_language: Option<&str>,
+    ) {
+        let Some(locale) = error_response
+            .meta
+            .as_ref()
+            .and_then(ResponseMeta::locale)
+            .and_then(|v| self.bundle(v))
+            .map(MessageBundle::locale)
+            .or_else(|| accept_language.and_then(|v| self.negotiate(v)))
+        else {
+            return;
+        };
+        if self.localize_error(&mut error_response.error, locale) {
+            if let Some(meta) = error_response.meta.as_mut() {
+                meta.set_locale(locale);
+            }
+        }
+    }
+}
+
+/// Replace each `{key}` with the detail of the same key; unknown keys are
+/// kept as is.
+fn fill_placeholders(template: &str, details: Option<&HashMap<String, String>>) -> String {
+    let Some(values) = details else {
+        return template.to_owned();
+    };
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let (head, tail) = rest.split_at(start);
+        filled.push_str(head);
+        match tail.find('}') {
+            Some(end) => {
+                let placeholder = tail.get(..=end).unwrap_or(tail);
+                let key = placeholder.get(1..end).unwrap_or_default();
+                filled.push_str(values.get(key).map_or(placeholder, String::as_str));
+                rest = tail.get(end + 1..).unwrap_or_default();
+            }
+            None => {
+                filled.push_str(tail);
+                rest = "";
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::shadow_unrelated)]
+mod tests {
+    use super::{Localizer, MessageBundle};
+    use crate::{
+        ApiResponse, DefaultMeta, ErrCode,
+        error_code::{ErrPathRoot, ety_grpc},
+    };
+
+    fn localizer() -> Localizer {
+        Localizer::new()
+            .with_bundle(
+                MessageBundle::from_json(
+                    "zh",
+                    r#"{"T1004": "未找到实体。", "1002000120": "邮箱 {email} 无效。"}"#,
+                )
+                .unwrap(),
+            )
+            .with_bundle(MessageBundle::new("fr").with_type(ety_grpc::NOT_FOUND, "Entité introuvable."))
+    }
+
+    #[test]
+    fn localize() {
+        let localizer = localizer();
+        let ep = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        assert_eq!(Some("zh"), localizer.negotiate("en-US;q=0.9, zh-CN, fr;q=0.8"));
+        assert_eq!(Some("fr"), localizer.negotiate("de, fr;q=0.5, zh;q=0"));
+        assert_eq!(None, localizer.negotiate("*"));
+
+        let invalid = ety_grpc::INVALID_ARGUMENT
+            .api_error(&ep)
+            .with_detail("email", "a@b")
+            .with_detail("other", "{email}");
+        assert_eq!(
+            Some("邮箱 a@b 无效。".to_owned()),
+            localizer.message(&invalid, "zh-Hans-CN")
+        );
+        assert_eq!(None, localizer.message(&invalid, "fr"));
+
+        let symbolic =
+            MessageBundle::new("de").with_code(ErrCode::new(0).with_symbol("USER_NOT_FOUND"), "Kein Benutzer.");
+        assert_eq!(
+            Some("Kein Benutzer."),
+            symbolic.message(&ErrCode::new(0).with_symbol("USER_NOT_FOUND"))
+        );
+        assert_eq!(
+            Some("Kein Benutzer."),
+            symbolic.message(&ErrCode::new(1004000120).with_symbol("USER_NOT_FOUND"))
+        );
+
+        let mut response: ApiResponse<(), DefaultMeta> = ety_grpc::NOT_FOUND.api_error(&ep).api_response_without_meta();
+        localizer.localize_response(&mut response, Some("fr-CA, zh;q=0.5"));
+        let ApiResponse::Error(error_response) = response else {
+            panic!("expected an error response");
+        };
+        assert_eq!("Entité introuvable.", error_response.error.message());
+
+        let mut response: ApiResponse<(), DefaultMeta> = ety_grpc::NOT_FOUND
+            .api_error(&ep)
+            .api_response_with_meta(DefaultMeta::new().with_locale("zh-CN"));
+        localizer.localize_response(&mut response, Some("fr"));
+        let ApiResponse::Error(error_response) = response else {
+            panic!("expected an error response");
+        };
+        assert_eq!("未找到实体。", error_response.error.message());
+        assert_eq!(Some(&"zh".to_owned()), error_response.meta.unwrap().locale());
+
+        let mut response: ApiResponse<(), ()> = ety_grpc::UNAVAILABLE.api_error(&ep).api_response_without_meta();
+        localizer.localize_response(&mut response, Some("zh"));
+        let ApiResponse::Error(error_response) = response else {
+            panic!("expected an error response");
+        };
+        assert_eq!(ety_grpc::UNAVAILABLE.text(), error_response.error.message());
+    }
+}
@@ -9,6 +9,10 @@
 //! * `meta`: Contains the meta structures.
 //! * `success`: Contains the success response structures.
 //! * `error`: Contains the error handling structures.
+//! * `i18n`: Localizes error messages by error code and locale.
+//! * `stream`: Contains the streaming encodings (NDJSON and SSE), requires the
+//!   `stream` feature.
+//! * `typescript`: Generates TypeScript types of the envelope and error codes.
 
 // -------- rust coding guidelines: https://rust-coding-guidelines.github.io/rust-coding-guidelines-zh/ --------
 // -------- rustc lint doc: https://doc.rust-lang.org/rustc/lints/listing/index.html --------
@@ -121,13 +125,38 @@ mod try_trait;
 #[cfg(feature = "salvo")]
 mod salvo_trait;
 
+#[cfg(feature = "axum")]
+mod axum_trait;
+
+#[cfg(feature = "reqwest")]
+mod reqwest_trait;
+
+#[cfg(feature = "utoipa")]
+mod utoipa_trait;
+
+#[cfg(feature = "schemars")]
+mod schemars_trait;
+
+#[cfg(feature = "tracing")]
+mod tracing_trait;
+
+#[cfg(feature = "signed-cursor")]
+pub mod cursor;
 mod error;
 pub mod error_code;
+pub mod i18n;
 #[cfg(feature = "lite")]
 pub(crate) mod lite;
 mod meta;
+#[cfg(feature = "tokio")]
+pub mod meta_context;
+pub mod meta_headers;
 mod result;
+#[cfg(feature = "stream")]
+pub mod stream;
 mod success;
+pub mod trace_context;
+pub mod typescript;
 mod utils;
 
 use std::{error::Error, fmt::Debug};
@@ -136,14 +165,28 @@ pub use prelude::*;
 pub mod prelude {
     pub use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
+    #[cfg(feature = "tokio")]
+    pub use crate::meta_context::{current_meta, scope_header_mapping, scope_localizer, scope_meta};
+    #[cfg(feature = "reqwest")]
+    pub use crate::reqwest_trait::{HTTP_BODY_DETAIL, HTTP_STATUS_DETAIL, ReqwestResponseExt};
+    #[cfg(feature = "salvo")]
+    pub use crate::salvo_trait::{ErrDeclList, OpenApiErrorCodes, WithErrDecls};
+    #[cfg(all(feature = "salvo", feature = "tokio"))]
+    pub use crate::salvo_trait::{Localize, MetaContext, MetaHeaders};
+    #[cfg(feature = "stream")]
+    pub use crate::stream::{ApiResponseStream, StreamChunk, StreamEnd, StreamFrame};
+    #[cfg(feature = "tracing")]
+    pub use crate::tracing_trait::REQUEST_ID_FIELD;
     pub use crate::{
         ApiResponse, api_err,
-        error::{ApiError, ErrorResponse},
+        error::{ApiError, CodeFormat, ErrCode, ErrorResponse},
         error_code,
         error_code::ety_grpc,
-        meta::{Cost, DefaultMeta, Pagination, RateLimit, UserMeta},
+        meta::{Cost, CursorPagination, DefaultMeta, Pagination, RateLimit, ResponseMeta, UserMeta},
+        meta_headers::{HeaderMapping, MetaPlacement},
         result::ApiResult,
         success::{ApiSuccessResponse, SuccessResponse},
+        trace_context::TraceParent,
         utils::{ErrWrapper, IntoError, MaybeString},
     };
 }
@@ -198,12 +241,12 @@ impl<Data, Meta> ApiResponse<Data, Meta> {
         Self::Error(ErrorResponse::from_error(error))
     }
     #[inline(always)]
-    pub fn from_error_msg(code: impl Into<u32>, message: impl Into<String>) -> Self {
+    pub fn from_error_msg(code: impl Into<ErrCode>, message: impl Into<String>) -> Self {
         Self::Error(ErrorResponse::from_error_msg(code, message))
     }
     #[inline(always)]
     pub fn from_error_source(
-        code: impl Into<u32>,
+        code: impl Into<ErrCode>,
         source: impl Error + Send + Sync + 'static,
         set_source_detail: bool,
         message: Option<String>,
@@ -232,6 +275,29 @@ impl<Data, Meta> ApiResponse<Data, Meta> {
         }
         self
     }
+    /// Merge `meta` into the meta, instead of replacing it as
+    /// [`set_meta`](Self::set_meta) does, see [`ResponseMeta::merge`].
+    pub fn merge_meta(&mut self, meta: Meta) -> &mut Self
+    where
+        Meta: ResponseMeta,
+    {
+        match self {
+            ApiResponse::Success(success_response) => {
+                success_response.merge_meta(meta);
+            }
+            ApiResponse::Error(error_response) => {
+                error_response.merge_meta(meta);
+            }
+        }
+        self
+    }
+    /// Serialize the error code, if any, in `format`, see [`CodeFormat`].
+    pub fn with_code_format(self, format: CodeFormat) -> Self {
+        match self {
+            ApiResponse::Success(_) => self,
+            ApiResponse::Error(error_response) => ApiResponse::Error(error_response.with_code_format(format)),
+        }
+    }
     pub const fn is_success(&self) -> bool {
         matches!(self, Self::Success(_))
     }
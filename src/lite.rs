@@ -4,7 +4,7 @@ use serde::{
     ser::SerializeStruct,
 };
 
-use crate::{ApiError, ApiResponse, ErrorResponse, SuccessResponse, utils::OrderedHashMap};
+use crate::{ApiError, ApiResponse, ErrCode, ErrorResponse, SuccessResponse, utils::OrderedHashMap};
 
 impl<Data, Meta> Serialize for ApiResponse<Data, Meta>
 where
@@ -55,13 +55,13 @@ where
             ApiResponse::Error(ref field0) => {
                 if let Some(meta) = &field0.meta {
                     let mut state = serializer.serialize_struct("ApiResponse", 3)?;
-                    state.serialize_field("code", &field0.code())?;
+                    state.serialize_field("code", &field0.error.code)?;
                     state.serialize_field("error", &__ApiError(&field0.error))?;
                     state.serialize_field("meta", meta)?;
                     state.end()
                 } else {
                     let mut state = serializer.serialize_struct("ApiResponse", 2)?;
-                    state.serialize_field("code", &field0.code())?;
+                    state.serialize_field("code", &field0.error.code)?;
                     state.serialize_field("error", &__ApiError(&field0.error))?;
                     state.end()
                 }
@@ -71,10 +71,17 @@ where
 }
 
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 #[derive(Deserialize)]
 pub(crate) struct __ApiError {
     message: String,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<std::collections::HashMap<String, String>>))]
+    #[cfg_attr(
+        feature = "schemars",
+        schemars(with = "Option<std::collections::HashMap<String, String>>")
+    )]
     details: Option<OrderedHashMap<String, String>>,
 }
 
@@ -150,9 +157,9 @@ where
                     }
                 }
 
-                let code: u32 = code.unwrap_or_default();
-                // let code: u32 = code.ok_or_else(|| de::Error::missing_field("code"))?;
-                if code == 0 {
+                let code: ErrCode = code.unwrap_or_default();
+                // let code: ErrCode = code.ok_or_else(|| de::Error::missing_field("code"))?;
+                if code.is_zero() {
                     let data: Data = data.ok_or_else(|| de::Error::missing_field("data"))?;
                     Ok(ApiResponse::Success(SuccessResponse { data, meta }))
                 } else {
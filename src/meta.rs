@@ -8,29 +8,54 @@ use crate::{MaybeString, utils::OrderedHashMap};
 
 /// Default meta type
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
-#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[non_exhaustive]
 pub struct DefaultMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub request_id: Option<String>,
+    /// The W3C trace id, 32 lowercase hex digits, see
+    /// [`TraceParent`](crate::trace_context::TraceParent).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// The W3C id of the span of the request, 16 lowercase hex digits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+    /// The W3C trace flags, e.g. `1` when sampled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_flags: Option<u8>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub user: Option<UserMeta>,
+    /// The pagination of a collection paged by page numbers, set instead of
+    /// `cursor_pagination`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pagination: Option<Pagination>,
+    /// The pagination of a collection paged by cursors, set instead of
+    /// `pagination`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor_pagination: Option<CursorPagination>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub rate_limit: Option<RateLimit>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub cost: Option<Cost>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub api_version: Option<String>,
+    /// The locale of the messages, e.g. `zh-CN`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    #[cfg_attr(feature = "utoipa", schema(value_type = HashMap<String, String>))]
+    #[cfg_attr(feature = "schemars", schemars(with = "HashMap<String, String>"))]
     custom: OrderedHashMap<String, String>,
 }
 
 /// The user's permission information and so on.
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
-#[derive(Default, Getset2, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Default, Getset2, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[getset2(get_ref, set_with)]
 #[non_exhaustive]
@@ -41,7 +66,9 @@ pub struct UserMeta {
 
 /// Pagination information.
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
-#[derive(Default, Getset2, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Default, Getset2, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[getset2(get_ref(pub), set_with(pub))]
 #[non_exhaustive]
@@ -54,9 +81,64 @@ pub struct Pagination {
     pub prev_page: Option<u32>,
 }
 
+impl Pagination {
+    /// The pagination of the 1-based `page` of a collection of
+    /// `total_records`; a `page` of 0 is the first one, and a `page_size` of
+    /// 0 has no page.
+    ///
+    /// `prev_page` is the page before `page` clamped to the last page, so a
+    /// `page` past the end points back to the last page; it is `None` on the
+    /// first page and when there is no page.
+    pub const fn from_total(page: u32, page_size: u32, total_records: u32) -> Self {
+        let current_page = if page == 0 { 1 } else { page };
+        let total_pages = match total_records.checked_div(page_size) {
+            Some(pages) if !total_records.is_multiple_of(page_size) => pages + 1,
+            Some(pages) => pages,
+            None => 0,
+        };
+        Self {
+            current_page,
+            page_size,
+            total_pages,
+            total_records,
+            next_page: if current_page < total_pages {
+                Some(current_page + 1)
+            } else {
+                None
+            },
+            prev_page: if current_page == 1 || total_pages == 0 {
+                None
+            } else if current_page > total_pages {
+                Some(total_pages)
+            } else {
+                Some(current_page - 1)
+            },
+        }
+    }
+}
+
+/// Pagination information of a collection paged by opaque cursors; the
+/// `signed-cursor` feature signs them with `cursor::CursorSigner`.
+#[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Default, Getset2, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[getset2(get_ref(pub), set_with(pub))]
+#[non_exhaustive]
+pub struct CursorPagination {
+    pub next_cursor: Option<String>,
+    pub prev_cursor: Option<String>,
+    pub has_more: bool,
+    /// The total number of records, when it is known or estimated.
+    pub estimated_total: Option<u64>,
+}
+
 /// Rate limiting information.
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
-#[derive(Default, Getset2, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Default, Getset2, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[getset2(set_with)]
 #[non_exhaustive]
@@ -69,7 +151,9 @@ pub struct RateLimit {
 
 /// Cost and cost statistics.
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
-#[derive(Default, Getset2, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[derive(Default, Getset2, Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[getset2(set_with)]
 #[non_exhaustive]
@@ -90,11 +174,16 @@ impl DefaultMeta {
     pub fn new() -> Self {
         Self {
             request_id: None,
+            trace_id: None,
+            span_id: None,
+            trace_flags: None,
             user: None,
             pagination: None,
+            cursor_pagination: None,
             rate_limit: None,
             cost: None,
             api_version: None,
+            locale: None,
             custom: OrderedHashMap::default(),
         }
     }
@@ -104,15 +193,41 @@ impl DefaultMeta {
         self
     }
     #[inline(always)]
+    pub fn with_trace_id(mut self, trace_id: impl Into<MaybeString>) -> Self {
+        self.trace_id = trace_id.into().option_string();
+        self
+    }
+    #[inline(always)]
+    pub fn with_span_id(mut self, span_id: impl Into<MaybeString>) -> Self {
+        self.span_id = span_id.into().option_string();
+        self
+    }
+    #[inline(always)]
+    pub const fn with_trace_flags(mut self, trace_flags: Option<u8>) -> Self {
+        self.trace_flags = trace_flags;
+        self
+    }
+    #[inline(always)]
     pub fn with_user(mut self, user: Option<UserMeta>) -> Self {
         self.user = user;
         self
     }
+    /// Set the page-number pagination. Being `const`, it cannot drop a
+    /// `cursor_pagination` already set, so call it on a meta without one.
     #[inline(always)]
     pub const fn with_pagination(mut self, pagination: Option<Pagination>) -> Self {
         self.pagination = pagination;
         self
     }
+    /// Set the cursor pagination; `Some` clears the `pagination`.
+    #[inline(always)]
+    pub fn with_cursor_pagination(mut self, cursor_pagination: Option<CursorPagination>) -> Self {
+        if cursor_pagination.is_some() {
+            self.pagination = None;
+        }
+        self.cursor_pagination = cursor_pagination;
+        self
+    }
     #[inline(always)]
     pub const fn with_rate_limit(mut self, rate_limit: Option<RateLimit>) -> Self {
         self.rate_limit = rate_limit;
@@ -129,6 +244,11 @@ impl DefaultMeta {
         self
     }
     #[inline(always)]
+    pub fn with_locale(mut self, locale: impl Into<MaybeString>) -> Self {
+        self.locale = locale.into().option_string();
+        self
+    }
+    #[inline(always)]
     pub fn insert_custom(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
         self.custom.insert(key.into(), value.into());
         self
@@ -137,12 +257,24 @@ impl DefaultMeta {
     pub const fn request_id(&self) -> Option<&String> {
         self.request_id.as_ref()
     }
+    pub const fn trace_id(&self) -> Option<&String> {
+        self.trace_id.as_ref()
+    }
+    pub const fn span_id(&self) -> Option<&String> {
+        self.span_id.as_ref()
+    }
+    pub const fn trace_flags(&self) -> Option<u8> {
+        self.trace_flags
+    }
     pub const fn user(&self) -> Option<&UserMeta> {
         self.user.as_ref()
     }
     pub const fn pagination(&self) -> Option<&Pagination> {
         self.pagination.as_ref()
     }
+    pub const fn cursor_pagination(&self) -> Option<&CursorPagination> {
+        self.cursor_pagination.as_ref()
+    }
     pub const fn rate_limit(&self) -> Option<&RateLimit> {
         self.rate_limit.as_ref()
     }
@@ -152,6 +284,9 @@ impl DefaultMeta {
     pub const fn api_version(&self) -> Option<&String> {
         self.api_version.as_ref()
     }
+    pub const fn locale(&self) -> Option<&String> {
+        self.locale.as_ref()
+    }
     pub fn custom(&self) -> &HashMap<String, String> {
         &self.custom
     }
@@ -159,3 +294,265 @@ impl DefaultMeta {
         self.custom.get(key.as_ref())
     }
 }
+
+/// A response meta that layered components contribute to, see
+/// [`ApiResponse::merge_meta`](crate::ApiResponse::merge_meta).
+///
+/// The `salvo` and `axum` integrations render the metas implementing it, and
+/// fill them from [`from_context`](Self::from_context) with the same merge.
+/// Every method has a default, so a custom meta opts in with an empty impl:
+///
+/// ```
+/// # use api_response::ResponseMeta;
+/// #[derive(serde::Serialize)]
+/// struct TenantMeta {
+///     tenant: String,
+/// }
+///
+/// impl ResponseMeta for TenantMeta {}
+/// ```
+pub trait ResponseMeta {
+    /// Merge `other` into `self`; what `other` sets takes precedence. By
+    /// default `other` replaces `self` as a whole.
+    #[inline(always)]
+    fn merge(&mut self, other: Self)
+    where
+        Self: Sized,
+    {
+        *self = other;
+    }
+    /// The meta of the request-scoped meta context, if any, that the `salvo`
+    /// and `axum` integrations merge the rendered meta into. None by default.
+    #[inline(always)]
+    fn from_context() -> Option<Self>
+    where
+        Self: Sized,
+    {
+        None
+    }
+    /// The id of the request, that the `tracing` feature adds to the events of
+    /// the rendered errors. None by default.
+    #[inline(always)]
+    fn request_id(&self) -> Option<&str> {
+        None
+    }
+    /// The locale the [`Localizer`](crate::i18n::Localizer) localizes the
+    /// error with, before the `Accept-Language` of the request. None by
+    /// default.
+    #[inline(always)]
+    fn locale(&self) -> Option<&str> {
+        None
+    }
+    /// Record the locale the error was localized to. Does nothing by default.
+    #[inline(always)]
+    fn set_locale(&mut self, _locale: &str) {}
+    /// Emit the fields placed in the headers on the response headers, and
+    /// remove the ones placed only there, once the `salvo` and `axum`
+    /// integrations filled the meta. Does nothing by default.
+    #[inline(always)]
+    fn emit_headers(&mut self, _headers: &mut http::HeaderMap) {}
+}
+
+impl ResponseMeta for () {}
+
+/// The `Option` fields set in `other` replace the ones of `self` as a whole,
+/// e.g. `pagination` is never mixed from both, and setting one of
+/// `pagination` and `cursor_pagination` clears the other; the `custom` entries
+/// are merged by key.
+impl ResponseMeta for DefaultMeta {
+    fn merge(&mut self, other: Self) {
+        let Self {
+            request_id,
+            trace_id,
+            span_id,
+            trace_flags,
+            user,
+            pagination,
+            cursor_pagination,
+            rate_limit,
+            cost,
+            api_version,
+            locale,
+            custom,
+        } = other;
+        merge_option(&mut self.request_id, request_id);
+        merge_option(&mut self.trace_id, trace_id);
+        merge_option(&mut self.span_id, span_id);
+        merge_option(&mut self.trace_flags, trace_flags);
+        merge_option(&mut self.user, user);
+        if pagination.is_some() {
+            self.cursor_pagination = None;
+        }
+        if cursor_pagination.is_some() {
+            self.pagination = None;
+        }
+        merge_option(&mut self.pagination, pagination);
+        merge_option(&mut self.cursor_pagination, cursor_pagination);
+        merge_option(&mut self.rate_limit, rate_limit);
+        merge_option(&mut self.cost, cost);
+        merge_option(&mut self.api_version, api_version);
+        merge_option(&mut self.locale, locale);
+        self.custom.extend(custom.0);
+    }
+    /// The request-scoped meta context, with the `tokio` feature.
+    fn from_context() -> Option<Self> {
+        #[cfg(feature = "tokio")]
+        {
+            crate::meta_context::current_meta()
+        }
+        #[cfg(not(feature = "tokio"))]
+        {
+            None
+        }
+    }
+    #[inline(always)]
+    fn request_id(&self) -> Option<&str> {
+        self.request_id.as_deref()
+    }
+    #[inline(always)]
+    fn locale(&self) -> Option<&str> {
+        self.locale.as_deref()
+    }
+    fn set_locale(&mut self, locale: &str) {
+        locale.clone_into(self.locale.get_or_insert_default());
+    }
+    /// With the [`HeaderMapping`](crate::meta_headers::HeaderMapping) of the
+    /// request-scoped context, with the `tokio` feature.
+    #[cfg(feature = "tokio")]
+    fn emit_headers(&mut self, headers: &mut http::HeaderMap) {
+        crate::meta_context::with_header_mapping(|mapping| mapping.emit(self, headers));
+    }
+}
+
+/// Merge `meta` into `field`, or set it when `field` is unset.
+pub(crate) fn merge_meta<Meta: ResponseMeta>(field: &mut Option<Meta>, meta: Meta) {
+    match field {
+        Some(current) => current.merge(meta),
+        None => *field = Some(meta),
+    }
+}
+
+/// Prepare the meta of a response rendered by the `salvo` or `axum`
+/// integrations: merge it into the meta context, so that the context fills the
+/// fields the handler did not set, localize the error with the localizer of
+/// the context, then emit the meta on the response headers, see
+/// [`ResponseMeta::from_context`], `meta_context::scope_localizer` and
+/// [`ResponseMeta::emit_headers`].
+#[cfg(any(feature = "salvo", feature = "axum"))]
+pub(crate) fn render_meta<Data, Meta: ResponseMeta>(
+    response: &mut crate::ApiResponse<Data, Meta>,
+    headers: &mut http::HeaderMap,
+) {
+    if let Some(mut meta) = Meta::from_context() {
+        let field = meta_field(response);
+        if let Some(handler_meta) = field.take() {
+            meta.merge(handler_meta);
+        }
+        *field = Some(meta);
+    }
+    #[cfg(feature = "tokio")]
+    crate::meta_context::with_localizer(|localizer, accept_language| {
+        localizer.localize_response(response, accept_language);
+    });
+    if let Some(meta) = meta_field(response) {
+        meta.emit_headers(headers);
+    }
+}
+
+#[cfg(any(feature = "salvo", feature = "axum"))]
+const fn meta_field<Data, Meta>(response: &mut crate::ApiResponse<Data, Meta>) -> &mut Option<Meta> {
+    match response {
+        crate::ApiResponse::Success(success_response) => &mut success_response.meta,
+        crate::ApiResponse::Error(error_response) => &mut error_response.meta,
+    }
+}
+
+#[inline(always)]
+fn merge_option<T>(field: &mut Option<T>, other: Option<T>) {
+    if other.is_some() {
+        *field = other;
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::default_numeric_fallback)]
+mod tests {
+    use super::{CursorPagination, DefaultMeta, Pagination, RateLimit, ResponseMeta};
+    use crate::{ApiResponse, ApiSuccessResponse};
+
+    #[test]
+    fn merge() {
+        let mut meta = DefaultMeta::new()
+            .with_request_id("req-1")
+            .with_pagination(Some(Pagination::default().with_current_page(2)))
+            .insert_custom("a", "1")
+            .insert_custom("b", "1");
+        meta.merge(
+            DefaultMeta::new()
+                .with_request_id("req-2")
+                .with_rate_limit(Some(RateLimit::default().with_remaining(9)))
+                .insert_custom("b", "2"),
+        );
+        assert_eq!(Some("req-2"), meta.request_id().map(String::as_str));
+        assert_eq!(2, meta.pagination().unwrap().current_page);
+        assert_eq!(9, meta.rate_limit().unwrap().remaining);
+        assert_eq!(Some("1"), meta.custom_kv("a").map(String::as_str));
+        assert_eq!(Some("2"), meta.custom_kv("b").map(String::as_str));
+    }
+
+    #[test]
+    fn merge_response_meta() {
+        let mut response: ApiResponse<u8, DefaultMeta> = 1.api_response_without_meta();
+        response.merge_meta(DefaultMeta::new().with_pagination(Some(Pagination::default().with_page_size(20))));
+        response.merge_meta(DefaultMeta::new().with_rate_limit(Some(RateLimit::default().with_limit(100))));
+        let meta = response.get_meta().unwrap();
+        assert_eq!(20, meta.pagination().unwrap().page_size);
+        assert_eq!(100, meta.rate_limit().unwrap().limit);
+    }
+
+    #[test]
+    fn pagination_from_total() {
+        let pagination = Pagination::from_total(2, 10, 25);
+        assert_eq!(3, pagination.total_pages);
+        assert_eq!((Some(3), Some(1)), (pagination.next_page, pagination.prev_page));
+        let last = Pagination::from_total(3, 10, 30);
+        assert_eq!((None, Some(2)), (last.next_page, last.prev_page));
+        let empty = Pagination::from_total(1, 10, 0);
+        assert_eq!((0, None, None), (empty.total_pages, empty.next_page, empty.prev_page));
+        assert_eq!(0, Pagination::from_total(1, 0, 5).total_pages);
+        assert_eq!(Some(3), Pagination::from_total(4, 10, 25).prev_page);
+        assert_eq!(Some(3), Pagination::from_total(9, 10, 25).prev_page);
+        assert_eq!(None, Pagination::from_total(9, 10, 0).prev_page);
+        let first = Pagination::from_total(0, 10, 25);
+        assert_eq!(
+            (1, Some(2), None),
+            (first.current_page, first.next_page, first.prev_page)
+        );
+        let max = Pagination::from_total(u32::MAX, 1, u32::MAX);
+        assert_eq!((None, Some(u32::MAX - 1)), (max.next_page, max.prev_page));
+    }
+
+    #[test]
+    fn paging() {
+        let mut meta = DefaultMeta::new().with_pagination(Some(Pagination::default().with_current_page(2)));
+        meta.merge(DefaultMeta::new().with_cursor_pagination(Some(CursorPagination::default().with_has_more(true))));
+        assert!(meta.pagination().is_none());
+        assert!(meta.cursor_pagination().unwrap().has_more);
+
+        let json = serde_json::to_string(&meta).unwrap();
+        assert_eq!(
+            r#"{"cursorPagination":{"nextCursor":null,"prevCursor":null,"hasMore":true,"estimatedTotal":null}}"#,
+            json
+        );
+        let decoded: DefaultMeta = serde_json::from_str(&json).unwrap();
+        assert!(decoded.cursor_pagination().unwrap().has_more);
+        let unpaged: DefaultMeta = serde_json::from_str(r#"{"requestId":"req-1"}"#).unwrap();
+        assert!(unpaged.pagination().is_none() && unpaged.cursor_pagination().is_none());
+        assert!(serde_json::from_str::<DefaultMeta>(r#"{"pagination":{"currentPage":"x"}}"#).is_err());
+
+        let cursor = DefaultMeta::new()
+            .with_pagination(Some(Pagination::default()))
+            .with_cursor_pagination(Some(CursorPagination::default()));
+        assert!(cursor.pagination().is_none());
+    }
+}
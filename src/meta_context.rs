@@ -0,0 +1,120 @@
+//! A request-scoped `DefaultMeta`, held in a tokio task-local.
+//!
+//! A middleware builds the meta of the request once and runs the rest of the
+//! request in its [`scope_meta`]; the `salvo` and `axum` integrations then
+//! fill the fields of the rendered `meta` that the handler did not set, by
+//! merging the handler's meta into the context, see
+//! [`ResponseMeta::from_context`](crate::ResponseMeta::from_context).
+//!
+//! Likewise, a [`HeaderMapping`] in [`scope_header_mapping`] is used to
+//! emit the rendered `DefaultMeta` on the response headers, see
+//! [`HeaderMapping::emit`].
+//!
+//! A [`Localizer`] in [`scope_localizer`] localizes the rendered error
+//! responses, with the locale of the meta or else the `Accept-Language` of the
+//! request, see [`Localizer::localize_response`].
+//!
+//! With `salvo`, the `MetaHeaders` hoop extracts the meta from the request
+//! headers into the `Depot` and scopes its mapping, the `MetaContext` hoop
+//! scopes the `DefaultMeta` injected in the `Depot`, and the `Localize` hoop
+//! scopes its localizer. With `axum`:
+//!
+//! ```ignore
+//! async fn meta_context(request: Request, next: Next) -> Response {
+//!     let mapping = HeaderMapping::default();
+//!     let meta = mapping.extract(request.headers());
+//!     scope_header_mapping(mapping, scope_meta(meta, next.run(request))).await
+//! }
+//! ```
+
+use std::{future::Future, sync::Arc};
+
+use crate::{DefaultMeta, i18n::Localizer, meta_headers::HeaderMapping};
+
+/// The localizer of the context and the `Accept-Language` of the request.
+struct LocalizerContext {
+    localizer: Arc<Localizer>,
+    accept_language: Option<String>,
+}
+
+tokio::task_local! {
+    static META_CONTEXT: DefaultMeta;
+    static HEADER_MAPPING: HeaderMapping;
+    static LOCALIZER: LocalizerContext;
+}
+
+/// Run the future with `meta` as the meta context.
+pub async fn scope_meta<F: Future>(meta: DefaultMeta, f: F) -> F::Output {
+    META_CONTEXT.scope(meta, f).await
+}
+
+/// Run the closure with `meta` as the meta context.
+pub fn sync_scope_meta<R>(meta: DefaultMeta, f: impl FnOnce() -> R) -> R {
+    META_CONTEXT.sync_scope(meta, f)
+}
+
+/// Call `f` with the meta context, if any.
+pub fn with_current_meta<R>(f: impl FnOnce(&DefaultMeta) -> R) -> Option<R> {
+    META_CONTEXT.try_with(f).ok()
+}
+
+/// A clone of the meta context, if any.
+pub fn current_meta() -> Option<DefaultMeta> {
+    with_current_meta(Clone::clone)
+}
+
+/// Run the future with `mapping` emitting the rendered meta on the response
+/// headers.
+pub async fn scope_header_mapping<F: Future>(mapping: HeaderMapping, f: F) -> F::Output {
+    HEADER_MAPPING.scope(mapping, f).await
+}
+
+/// Call `f` with the header mapping of the context, if any.
+pub fn with_header_mapping<R>(f: impl FnOnce(&HeaderMapping) -> R) -> Option<R> {
+    HEADER_MAPPING.try_with(f).ok()
+}
+
+/// Run the future with `localizer` localizing the rendered error responses,
+/// with `accept_language` the `Accept-Language` header of the request.
+pub async fn scope_localizer<F: Future>(localizer: Arc<Localizer>, accept_language: Option<String>, f: F) -> F::Output {
+    let context = LocalizerContext {
+        localizer,
+        accept_language,
+    };
+    LOCALIZER.scope(context, f).await
+}
+
+/// Call `f` with the localizer of the context and the `Accept-Language` of the
+/// request, if any.
+pub fn with_localizer<R>(f: impl FnOnce(&Localizer, Option<&str>) -> R) -> Option<R> {
+    LOCALIZER
+        .try_with(|context| f(&context.localizer, context.accept_language.as_deref()))
+        .ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::default_numeric_fallback)]
+mod tests {
+    use super::{current_meta, scope_meta, sync_scope_meta};
+    use crate::{DefaultMeta, Pagination, ResponseMeta};
+
+    #[tokio::test]
+    async fn context_meta() {
+        assert!(current_meta().is_none());
+        assert!(DefaultMeta::from_context().is_none());
+        let context = DefaultMeta::new().with_request_id("req-1").with_api_version("v2");
+        let mut meta = scope_meta(context.clone(), async { DefaultMeta::from_context() })
+            .await
+            .unwrap();
+        meta.merge(
+            DefaultMeta::new()
+                .with_api_version("v3")
+                .with_pagination(Some(Pagination::default().with_page_size(10))),
+        );
+        assert_eq!(Some("req-1"), meta.request_id().map(String::as_str));
+        assert_eq!(Some("v3"), meta.api_version().map(String::as_str));
+        assert_eq!(10, meta.pagination().unwrap().page_size);
+
+        assert!(sync_scope_meta(context, <()>::from_context).is_none());
+    }
+}
@@ -0,0 +1,322 @@
+//! The mapping between `DefaultMeta` and HTTP headers: the request id, the
+//! `traceparent` and the API version are extracted from the request headers,
+//! and emitted with the rate limit on the response headers.
+//!
+//! ```
+//! use api_response::prelude::*;
+//! use http::HeaderMap;
+//!
+//! let mut request_headers = HeaderMap::new();
+//! request_headers.insert("x-request-id", "req-1".parse().unwrap());
+//! let mut meta = DefaultMeta::from_headers(&request_headers)
+//!     .with_rate_limit(Some(RateLimit::default().with_limit(100).with_remaining(99)));
+//!
+//! let mapping = HeaderMapping::default().with_rate_limit(MetaPlacement::Header);
+//! let mut response_headers = HeaderMap::new();
+//! mapping.emit(&mut meta, &mut response_headers);
+//! assert_eq!("req-1", response_headers["x-request-id"]);
+//! assert_eq!("99", response_headers["ratelimit-remaining"]);
+//! assert!(meta.rate_limit().is_none());
+//! ```
+//!
+//! With the `tokio` feature, the `salvo` and `axum` integrations emit the
+//! rendered meta with the mapping of
+//! [`scope_header_mapping`](crate::meta_context::scope_header_mapping), or of
+//! the `MetaHeaders` hoop of `salvo`.
+
+use chrono::{DateTime, TimeDelta, Utc};
+use http::{HeaderMap, HeaderName, HeaderValue, header::RETRY_AFTER};
+
+use crate::{ApiResponse, DefaultMeta, RateLimit, trace_context::TraceParent};
+
+/// The default header of the request id.
+pub const X_REQUEST_ID: HeaderName = HeaderName::from_static("x-request-id");
+/// The W3C trace context, see [`TraceParent`].
+pub const TRACEPARENT: HeaderName = HeaderName::from_static("traceparent");
+/// The default header of the API version.
+pub const API_VERSION: HeaderName = HeaderName::from_static("api-version");
+/// The request quota of the rate limit window.
+pub const RATELIMIT_LIMIT: HeaderName = HeaderName::from_static("ratelimit-limit");
+/// The requests left in the rate limit window.
+pub const RATELIMIT_REMAINING: HeaderName = HeaderName::from_static("ratelimit-remaining");
+/// The seconds until the rate limit resets.
+pub const RATELIMIT_RESET: HeaderName = HeaderName::from_static("ratelimit-reset");
+
+/// Where a meta field goes in a response.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MetaPlacement {
+    /// Only in the `meta` of the body.
+    Body,
+    /// Only in the headers; the field is removed from the `meta`.
+    Header,
+    #[default]
+    Both,
+}
+
+impl MetaPlacement {
+    #[inline]
+    pub const fn in_body(self) -> bool {
+        !matches!(self, Self::Header)
+    }
+    #[inline]
+    pub const fn in_header(self) -> bool {
+        !matches!(self, Self::Body)
+    }
+}
+
+/// The header names of the meta fields and where the fields go in responses.
+///
+/// By default every field is kept in the body and also emitted as a header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HeaderMapping {
+    pub request_id_header: HeaderName,
+    pub api_version_header: HeaderName,
+    pub request_id: MetaPlacement,
+    /// The placement of the `traceId`, `spanId` and `traceFlags`, as
+    /// `traceparent`.
+    pub trace: MetaPlacement,
+    pub api_version: MetaPlacement,
+    /// The placement of `RateLimit-Limit`, `RateLimit-Remaining`,
+    /// `RateLimit-Reset` and, once the limit is exhausted, `Retry-After`.
+    pub rate_limit: MetaPlacement,
+}
+
+impl Default for HeaderMapping {
+    fn default() -> Self {
+        Self {
+            request_id_header: X_REQUEST_ID,
+            api_version_header: API_VERSION,
+            request_id: MetaPlacement::Both,
+            trace: MetaPlacement::Both,
+            api_version: MetaPlacement::Both,
+            rate_limit: MetaPlacement::Both,
+        }
+    }
+}
+
+impl HeaderMapping {
+    #[inline]
+    pub fn with_request_id_header(mut self, name: HeaderName) -> Self {
+        self.request_id_header = name;
+        self
+    }
+    #[inline]
+    pub fn with_api_version_header(mut self, name: HeaderName) -> Self {
+        self.api_version_header = name;
+        self
+    }
+    #[inline]
+    pub const fn with_request_id(mut self, placement: MetaPlacement) -> Self {
+        self.request_id = placement;
+        self
+    }
+    #[inline]
+    pub const fn with_trace(mut self, placement: MetaPlacement) -> Self {
+        self.trace = placement;
+        self
+    }
+    #[inline]
+    pub const fn with_api_version(mut self, placement: MetaPlacement) -> Self {
+        self.api_version = placement;
+        self
+    }
+    #[inline]
+    pub const fn with_rate_limit(mut self, placement: MetaPlacement) -> Self {
+        self.rate_limit = placement;
+        self
+    }
+
+    /// Build a meta from the headers: the request id, the trace and span ids
+    /// of a valid `traceparent`, the API version and, from the headers of a
+    /// response, the rate limit.
+    pub fn extract(&self, headers: &HeaderMap) -> DefaultMeta {
+        self.extract_at(headers, Utc::now())
+    }
+
+    /// [`extract`](Self::extract) with `RateLimit-Reset` relative to `now`.
+    pub fn extract_at(&self, headers: &HeaderMap, now: DateTime<Utc>) -> DefaultMeta {
+        let text = |name: &HeaderName| headers.get(name)?.to_str().ok().map(str::trim);
+        let number = |name: &HeaderName| text(name)?.parse::<i64>().ok();
+        let rate_limit = match (number(&RATELIMIT_LIMIT), number(&RATELIMIT_REMAINING)) {
+            (Some(limit), Some(remaining)) => Some(RateLimit {
+                limit: i32::try_from(limit).unwrap_or(i32::MAX),
+                remaining: i32::try_from(remaining).unwrap_or(i32::MAX),
+                restore_rate: 0,
+                reset_at: number(&RATELIMIT_RESET).and_then(|v| now.checked_add_signed(TimeDelta::try_seconds(v)?)),
+            }),
+            _ => None,
+        };
+        let meta = DefaultMeta::new()
+            .with_request_id(text(&self.request_id_header).map(ToOwned::to_owned))
+            .with_api_version(text(&self.api_version_header).map(ToOwned::to_owned))
+            .with_rate_limit(rate_limit);
+        match text(&TRACEPARENT).and_then(|v| v.parse::<TraceParent>().ok()) {
+            Some(parent) => meta.with_trace_parent(parent),
+            None => meta,
+        }
+    }
+
+    /// Write the meta fields placed in the headers on the response headers,
+    /// and remove the ones placed only there from the meta.
+    pub fn emit(&self, meta: &mut DefaultMeta, headers: &mut HeaderMap) {
+        self.emit_at(meta, headers, Utc::now());
+    }
+
+    /// [`emit`](Self::emit) with `RateLimit-Reset` relative to `now`.
+    pub fn emit_at(&self, meta: &mut DefaultMeta, headers: &mut HeaderMap, now: DateTime<Utc>) {
+        if self.request_id.in_header() {
+            insert_str(headers, &self.request_id_header, meta.request_id());
+        }
+        if let Some(parent) = meta.trace_parent().filter(|_| self.trace.in_header()) {
+            insert_str(headers, &TRACEPARENT, Some(&parent.to_string()));
+        }
+        if self.api_version.in_header() {
+            insert_str(headers, &self.api_version_header, meta.api_version());
+        }
+        if let Some(rate_limit) = meta.rate_limit().filter(|_| self.rate_limit.in_header()) {
+            headers.insert(RATELIMIT_LIMIT, HeaderValue::from(rate_limit.limit));
+            headers.insert(RATELIMIT_REMAINING, HeaderValue::from(rate_limit.remaining));
+            if let Some(reset_at) = rate_limit.reset_at {
+                let seconds = (reset_at - now).num_seconds().max(0);
+                headers.insert(RATELIMIT_RESET, HeaderValue::from(seconds));
+                if rate_limit.remaining <= 0_i32 {
+                    headers.insert(RETRY_AFTER, HeaderValue::from(seconds));
+                }
+            }
+        }
+        if !self.request_id.in_body() {
+            meta.request_id = None;
+        }
+        if !self.trace.in_body() {
+            meta.trace_id = None;
+            meta.span_id = None;
+            meta.trace_flags = None;
+        }
+        if !self.api_version.in_body() {
+            meta.api_version = None;
+        }
+        if !self.rate_limit.in_body() {
+            meta.rate_limit = None;
+        }
+    }
+}
+
+fn insert_str(headers: &mut HeaderMap, name: &HeaderName, text: Option<&String>) {
+    if let Some(value) = text.and_then(|v| HeaderValue::from_str(v).ok()) {
+        headers.insert(name.clone(), value);
+    }
+}
+
+impl DefaultMeta {
+    /// Build a meta from the headers with the default [`HeaderMapping`].
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        HeaderMapping::default().extract(headers)
+    }
+}
+
+impl<Data> ApiResponse<Data, DefaultMeta> {
+    /// Emit the meta on the response headers, see [`HeaderMapping::emit`].
+    pub fn emit_headers(&mut self, mapping: &HeaderMapping, headers: &mut HeaderMap) {
+        let response_meta = match self {
+            ApiResponse::Success(success_response) => success_response.meta.as_mut(),
+            ApiResponse::Error(error_response) => error_response.meta.as_mut(),
+        };
+        if let Some(meta) = response_meta {
+            mapping.emit(meta, headers);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::default_numeric_fallback)]
+mod tests {
+    use chrono::{TimeDelta, Utc};
+    use http::{HeaderMap, header::RETRY_AFTER};
+
+    use super::{HeaderMapping, MetaPlacement};
+    use crate::{DefaultMeta, RateLimit};
+
+    #[test]
+    fn extract() {
+        let now = Utc::now();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-request-id", " req-1 ".parse().unwrap());
+        headers.insert("x-api-version", "2024-10-01".parse().unwrap());
+        headers.insert(
+            "traceparent",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+                .parse()
+                .unwrap(),
+        );
+        headers.insert("ratelimit-limit", "100".parse().unwrap());
+        headers.insert("ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("ratelimit-reset", "30".parse().unwrap());
+        let mapping = HeaderMapping::default().with_api_version_header("x-api-version".parse().unwrap());
+        let meta = mapping.extract_at(&headers, now);
+        assert_eq!(Some("req-1"), meta.request_id().map(String::as_str));
+        assert_eq!(Some("2024-10-01"), meta.api_version().map(String::as_str));
+        assert_eq!(
+            Some("4bf92f3577b34da6a3ce929d0e0e4736"),
+            meta.trace_id().map(String::as_str)
+        );
+        assert_eq!(Some("00f067aa0ba902b7"), meta.span_id().map(String::as_str));
+        assert_eq!(Some(1), meta.trace_flags());
+        let rate_limit = meta.rate_limit().unwrap();
+        assert_eq!((100, 0), (rate_limit.limit, rate_limit.remaining));
+        assert_eq!(Some(now + TimeDelta::seconds(30)), rate_limit.reset_at);
+        assert!(DefaultMeta::from_headers(&HeaderMap::new()).request_id().is_none());
+    }
+
+    #[test]
+    fn emit() {
+        let now = Utc::now();
+        let rate_limit = RateLimit {
+            limit: 100,
+            remaining: 0,
+            restore_rate: 0,
+            reset_at: Some(now + TimeDelta::seconds(30)),
+        };
+        let mut meta = DefaultMeta::new()
+            .with_request_id("req-1")
+            .with_trace_id("4bf92f3577b34da6a3ce929d0e0e4736")
+            .with_span_id("00f067aa0ba902b7")
+            .with_api_version("v2")
+            .with_rate_limit(Some(rate_limit));
+        let mapping = HeaderMapping::default()
+            .with_api_version(MetaPlacement::Body)
+            .with_rate_limit(MetaPlacement::Header);
+        let mut headers = HeaderMap::new();
+        mapping.emit_at(&mut meta, &mut headers, now);
+        assert_eq!("req-1", headers["x-request-id"]);
+        assert!(!headers.contains_key("api-version"));
+        assert_eq!(
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-00",
+            headers["traceparent"]
+        );
+        assert_eq!("100", headers["ratelimit-limit"]);
+        assert_eq!("0", headers["ratelimit-remaining"]);
+        assert_eq!("30", headers["ratelimit-reset"]);
+        assert_eq!("30", headers[RETRY_AFTER]);
+        assert_eq!(Some("req-1"), meta.request_id().map(String::as_str));
+        assert_eq!(Some("v2"), meta.api_version().map(String::as_str));
+        assert!(meta.rate_limit().is_none());
+    }
+
+    #[test]
+    fn trace_round_trip() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert("traceparent", traceparent.parse().unwrap());
+        let mut meta = DefaultMeta::from_headers(&request_headers);
+        let mut headers = HeaderMap::new();
+        HeaderMapping::default().emit(&mut meta, &mut headers);
+        assert_eq!(traceparent, headers["traceparent"]);
+
+        HeaderMapping::default()
+            .with_trace(MetaPlacement::Header)
+            .emit(&mut meta, &mut headers);
+        assert!(meta.trace_flags().is_none());
+    }
+}
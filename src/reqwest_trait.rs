@@ -0,0 +1,129 @@
+use std::future::Future;
+
+use http::StatusCode;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    ApiError, ApiResponse,
+    error_code::{ErrPath, ErrType, ety_grpc},
+};
+
+/// The detail key under which the original HTTP status is kept.
+pub const HTTP_STATUS_DETAIL: &str = "http_status";
+/// The detail key under which a non-envelope body is kept.
+pub const HTTP_BODY_DETAIL: &str = "http_body";
+/// The maximum number of characters of a non-envelope body kept in the details.
+const MAX_BODY_DETAIL_LEN: usize = 512;
+
+/// Decode a `reqwest` response into an `ApiResponse`.
+///
+/// * An envelope body is decoded as is, and the HTTP status is kept in the
+///   `http_status` detail of an error.
+/// * An empty body of a successful response is decoded as JSON `null`,
+///   otherwise an error is built from the HTTP status.
+/// * A non-envelope body, or a success envelope with a non-2xx status, becomes
+///   an error built from the HTTP status, with the (truncated) body kept in the
+///   `http_body` detail.
+/// * Transport errors become `ety_grpc::DEADLINE_EXCEEDED` when they are
+///   timeouts, and `ety_grpc::UNAVAILABLE` otherwise.
+///
+/// Errors built by the client use the default `ErrPath`, so their code is the
+/// `ErrType` flag followed by six zeros.
+pub trait ReqwestResponseExt {
+    fn decode_api_response<Data, Meta>(self) -> impl Future<Output = ApiResponse<Data, Meta>> + Send
+    where
+        Data: DeserializeOwned,
+        Meta: DeserializeOwned;
+}
+
+impl ReqwestResponseExt for reqwest::Response {
+    async fn decode_api_response<Data, Meta>(self) -> ApiResponse<Data, Meta>
+    where
+        Data: DeserializeOwned,
+        Meta: DeserializeOwned,
+    {
+        let status = self.status();
+        match self.bytes().await {
+            Ok(body) => decode_body(status, &body),
+            Err(e) => ApiResponse::from_error(ApiError::from(e).with_detail(HTTP_STATUS_DETAIL, status.as_str())),
+        }
+    }
+}
+
+impl ReqwestResponseExt for Result<reqwest::Response, reqwest::Error> {
+    async fn decode_api_response<Data, Meta>(self) -> ApiResponse<Data, Meta>
+    where
+        Data: DeserializeOwned,
+        Meta: DeserializeOwned,
+    {
+        match self {
+            Ok(response) => response.decode_api_response().await,
+            Err(e) => ApiResponse::from_error(e.into()),
+        }
+    }
+}
+
+impl From<reqwest::Error> for ApiError {
+    fn from(e: reqwest::Error) -> Self {
+        let err_type = if e.is_timeout() {
+            ety_grpc::DEADLINE_EXCEEDED
+        } else {
+            ety_grpc::UNAVAILABLE
+        };
+        let mut api_error = ApiError::from_source(err_type.extract(&ErrPath::default()).code(), e, true, ());
+        api_error.message = err_type.text().to_owned();
+        api_error
+    }
+}
+
+fn decode_body<Data, Meta>(status: StatusCode, body: &[u8]) -> ApiResponse<Data, Meta>
+where
+    Data: DeserializeOwned,
+    Meta: DeserializeOwned,
+{
+    if body.iter().all(u8::is_ascii_whitespace) {
+        if status.is_success() {
+            if let Ok(data) = serde_json::from_slice::<Data>(b"null") {
+                return ApiResponse::from_success(data);
+            }
+        }
+        return ApiResponse::from_error(status_error(status, "empty response body"));
+    }
+    match serde_json::from_slice::<ApiResponse<Data, Meta>>(body) {
+        Ok(ApiResponse::Error(mut error_response)) => {
+            error_response.set_detail(HTTP_STATUS_DETAIL, status.as_str());
+            ApiResponse::Error(error_response)
+        }
+        Ok(success) if status.is_success() => success,
+        Ok(_) => ApiResponse::from_error(
+            status_error(status, "success envelope with an error status")
+                .with_detail(HTTP_BODY_DETAIL, body_detail(body)),
+        ),
+        Err(e) => ApiResponse::from_error(
+            status_error(status, "non-envelope response body")
+                .with_detail(HTTP_BODY_DETAIL, body_detail(body))
+                .with_source(e, true),
+        ),
+    }
+}
+
+/// The body as text, truncated to `MAX_BODY_DETAIL_LEN` characters.
+fn body_detail(body: &[u8]) -> String {
+    String::from_utf8_lossy(body)
+        .chars()
+        .take(MAX_BODY_DETAIL_LEN)
+        .collect()
+}
+
+fn status_error(status: StatusCode, fallback_message: &str) -> ApiError {
+    let err_type = if status.is_success() {
+        ety_grpc::UNKNOWN
+    } else {
+        ErrType::from(status)
+    };
+    let message = status
+        .canonical_reason()
+        .map_or_else(|| fallback_message.to_owned(), ToOwned::to_owned);
+    ApiError::new(err_type.extract(&ErrPath::default()).code(), message)
+        .with_detail(HTTP_STATUS_DETAIL, status.as_str())
+}
@@ -1,45 +1,131 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use http::StatusCode;
 use salvo::{
     Scribe, async_trait,
-    oapi::{Components, ComposeSchema, Content, EndpointOutRegister, Operation, RefOr, Response, ToResponse, ToSchema},
+    oapi::{
+        BasicType, Components, ComposeSchema, Content, EndpointOutRegister, Example, Object, OpenApi, Operation, Ref,
+        RefOr, Response, ToResponse, ToSchema,
+        naming::{NameRule, assign_name},
+        schema::{KnownFormat, OneOf, SchemaFormat},
+    },
     writing::Json,
 };
 use serde::Serialize;
 
-use crate::ApiResponse;
-#[cfg(feature = "lite")]
-#[allow(dead_code)]
-#[derive(ToSchema)]
-struct ApiResponseSchema<Data, Meta> {
-    code: u32,
-    data: Data,
-    meta: Option<Meta>,
-    error: crate::lite::__ApiError,
-}
+use crate::{
+    ApiResponse, ErrCode, ResponseMeta,
+    error_code::{ErrDecl, tally::tally_err_decl},
+};
+
+/// Naming markers of the success and error branches of `ApiResponse`.
+struct ApiResponseSuccess<Data, Meta>(PhantomData<(Data, Meta)>);
+struct ApiResponseError<Meta>(PhantomData<Meta>);
 
 #[cfg(not(feature = "lite"))]
-#[allow(dead_code)]
-#[derive(ToSchema)]
-enum ApiStatus {
-    Success,
-    Error,
+fn success_schema(data: RefOr<salvo::oapi::schema::Schema>, meta: RefOr<salvo::oapi::schema::Schema>) -> Object {
+    Object::new()
+        .property(
+            "status",
+            Object::new().schema_type(BasicType::String).enum_values(["success"]),
+        )
+        .required("status")
+        .property("data", data)
+        .required("data")
+        .property("meta", meta)
 }
+
 #[cfg(not(feature = "lite"))]
-#[allow(dead_code)]
-#[derive(ToSchema)]
-struct ApiResponseSchema<Data, Meta> {
-    status: ApiStatus,
-    data: Data,
-    meta: Option<Meta>,
-    error: crate::ApiError,
+fn error_schema(components: &mut Components, meta: RefOr<salvo::oapi::schema::Schema>) -> Object {
+    Object::new()
+        .property(
+            "status",
+            Object::new().schema_type(BasicType::String).enum_values(["error"]),
+        )
+        .required("status")
+        .property("error", crate::ApiError::to_schema(components))
+        .required("error")
+        .property("meta", meta)
+}
+
+#[cfg(feature = "lite")]
+fn success_schema(data: RefOr<salvo::oapi::schema::Schema>, meta: RefOr<salvo::oapi::schema::Schema>) -> Object {
+    Object::new()
+        .property("code", Object::new().schema_type(BasicType::Integer).enum_values([0]))
+        .required("code")
+        .property("data", data)
+        .required("data")
+        .property("meta", meta)
+}
+
+#[cfg(feature = "lite")]
+fn error_schema(components: &mut Components, meta: RefOr<salvo::oapi::schema::Schema>) -> Object {
+    Object::new()
+        .property(
+            "code",
+            OneOf::new()
+                .item(Object::new().schema_type(BasicType::Integer).minimum(1))
+                .item(Object::new().schema_type(BasicType::String).min_length(1)),
+        )
+        .required("code")
+        .property("error", crate::lite::__ApiError::to_schema(components))
+        .required("error")
+        .property("meta", meta)
 }
 
+/// A numeric or a symbolic code, see `CodeFormat`.
+impl ToSchema for ErrCode {
+    fn to_schema(_components: &mut Components) -> RefOr<salvo::oapi::schema::Schema> {
+        OneOf::new()
+            .item(
+                Object::new()
+                    .schema_type(BasicType::Integer)
+                    .format(SchemaFormat::KnownFormat(KnownFormat::UInt64))
+                    .minimum(0),
+            )
+            .item(Object::new().schema_type(BasicType::String))
+            .into()
+    }
+}
+
+/// Describes `ApiResponse` as a `oneOf` of its success and error branches,
+/// discriminated by `status`. In lite mode it has no discriminator, whose
+/// property must be a string: the success `code` is `0` and the error
+/// `code` is positive or a string, so exactly one branch matches.
 impl<Data, Meta> ToSchema for ApiResponse<Data, Meta>
 where
     Data: ToSchema + ComposeSchema + 'static,
     Meta: ToSchema + ComposeSchema + 'static,
 {
     fn to_schema(components: &mut Components) -> RefOr<salvo::oapi::schema::Schema> {
-        ApiResponseSchema::<Data, Meta>::to_schema(components)
+        let name = assign_name::<Self>(NameRule::Auto);
+        if !components.schemas.contains_key(&name) {
+            let data = Data::to_schema(components);
+            let meta = Meta::to_schema(components);
+            let success_name = assign_name::<ApiResponseSuccess<Data, Meta>>(NameRule::Auto);
+            let success = success_schema(data, meta.clone());
+            components.schemas.insert(success_name.clone(), success);
+            let error_name = assign_name::<ApiResponseError<Meta>>(NameRule::Auto);
+            let error = error_schema(components, meta);
+            components.schemas.insert(error_name.clone(), error);
+
+            let success_ref = Ref::from_schema_name(&success_name);
+            let error_ref = Ref::from_schema_name(&error_name);
+            #[cfg(not(feature = "lite"))]
+            let one_of = {
+                let discriminator = salvo::oapi::schema::Discriminator::new("status")
+                    .add_mapping("success", success_ref.ref_location.clone())
+                    .add_mapping("error", error_ref.ref_location.clone());
+                OneOf::new()
+                    .item(success_ref)
+                    .item(error_ref)
+                    .discriminator(discriminator)
+            };
+            #[cfg(feature = "lite")]
+            let one_of = OneOf::new().item(success_ref).item(error_ref);
+            components.schemas.insert(name.clone(), one_of);
+        }
+        Ref::from_schema_name(name).into()
     }
 }
 
@@ -70,14 +156,306 @@ where
 impl<Data, Meta> Scribe for ApiResponse<Data, Meta>
 where
     Data: Serialize + Send,
-    Meta: Serialize + Send,
+    Meta: Serialize + Send + ResponseMeta,
 {
-    fn render(self, res: &mut salvo::prelude::Response) {
+    /// The meta is filled from the meta context and emitted on the headers,
+    /// see [`ResponseMeta`].
+    fn render(mut self, res: &mut salvo::prelude::Response) {
+        if let ApiResponse::Error(error_response) = &self {
+            crate::error_code::counter::record_rendered(&error_response.error);
+            #[cfg(feature = "tracing")]
+            error_response.trace();
+        }
+        crate::meta::render_meta(&mut self, res.headers_mut());
         Json(self).render(res)
     }
 }
 
+/// A hoop running the rest of the request with the `DefaultMeta` injected in
+/// the `Depot` as the meta context, see
+/// [`meta_context`](crate::meta_context).
+///
+/// Hoops injecting the meta go before it:
+///
+/// ```ignore
+/// Router::new().hoop(inject_request_meta).hoop(MetaContext).get(handler)
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::exhaustive_structs)]
+pub struct MetaContext;
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl salvo::Handler for MetaContext {
+    async fn handle(
+        &self,
+        req: &mut salvo::Request,
+        depot: &mut salvo::Depot,
+        res: &mut salvo::prelude::Response,
+        ctrl: &mut salvo::FlowCtrl,
+    ) {
+        let meta = depot
+            .get_typed::<crate::DefaultMeta>()
+            .ok()
+            .cloned()
+            .unwrap_or_default();
+        crate::meta_context::scope_meta(meta, ctrl.call_next(req, depot, res)).await;
+    }
+}
+
+/// A hoop extracting the meta of the request headers with its mapping, and
+/// running the rest of the request with the mapping emitting the rendered meta
+/// on the response headers, see [`meta_context`](crate::meta_context).
+///
+/// The extracted meta is stored in the `Depot`, merged with the `DefaultMeta`
+/// already injected there, whose fields take precedence. It goes before the
+/// [`MetaContext`] hoop:
+///
+/// ```ignore
+/// Router::new()
+///     .hoop(inject_request_meta)
+///     .hoop(MetaHeaders::default())
+///     .hoop(MetaContext)
+///     .get(handler)
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct MetaHeaders {
+    pub mapping: crate::HeaderMapping,
+}
+
+#[cfg(feature = "tokio")]
+impl MetaHeaders {
+    #[inline]
+    pub const fn new(mapping: crate::HeaderMapping) -> Self {
+        Self { mapping }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl salvo::Handler for MetaHeaders {
+    async fn handle(
+        &self,
+        req: &mut salvo::Request,
+        depot: &mut salvo::Depot,
+        res: &mut salvo::prelude::Response,
+        ctrl: &mut salvo::FlowCtrl,
+    ) {
+        let mut meta = self.mapping.extract(req.headers());
+        if let Ok(injected) = depot.get_typed::<crate::DefaultMeta>() {
+            meta.merge(injected.clone());
+        }
+        depot.insert_typed(meta);
+        crate::meta_context::scope_header_mapping(self.mapping.clone(), ctrl.call_next(req, depot, res)).await;
+    }
+}
+
+/// A hoop running the rest of the request with its localizer localizing the
+/// rendered error responses, with the locale of the meta or else the
+/// `Accept-Language` header of the request, see
+/// [`meta_context`](crate::meta_context).
+///
+/// ```ignore
+/// Router::new().hoop(Localize::new(Arc::new(localizer))).get(handler)
+/// ```
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Localize {
+    pub localizer: std::sync::Arc<crate::i18n::Localizer>,
+}
+
+#[cfg(feature = "tokio")]
+impl Localize {
+    #[inline]
+    pub const fn new(localizer: std::sync::Arc<crate::i18n::Localizer>) -> Self {
+        Self { localizer }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl salvo::Handler for Localize {
+    async fn handle(
+        &self,
+        req: &mut salvo::Request,
+        depot: &mut salvo::Depot,
+        res: &mut salvo::prelude::Response,
+        ctrl: &mut salvo::FlowCtrl,
+    ) {
+        let accept_language = req
+            .headers()
+            .get(http::header::ACCEPT_LANGUAGE)
+            .and_then(|v| v.to_str().ok())
+            .map(ToOwned::to_owned);
+        crate::meta_context::scope_localizer(self.localizer.clone(), accept_language, ctrl.call_next(req, depot, res))
+            .await;
+    }
+}
+
+/// The list of error declarations a handler can return.
+///
+/// Used with [`WithErrDecls`] to document them on the OpenAPI operation.
+pub trait ErrDeclList {
+    fn err_decls() -> Vec<ErrDecl>;
+}
+
+/// Wraps the return value of an endpoint and documents the `ErrDecl`s of `L`
+/// as responses of its OpenAPI operation.
+///
+/// The declarations are grouped by their HTTP status, and each group carries
+/// one example per error code and an `x-error-codes` extension.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct WithErrDecls<R, L> {
+    pub inner: R,
+    marker: PhantomData<fn() -> L>,
+}
+
+impl<R, L> WithErrDecls<R, L> {
+    #[inline]
+    pub const fn new(inner: R) -> Self {
+        Self {
+            inner,
+            marker: PhantomData,
+        }
+    }
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R, L> From<R> for WithErrDecls<R, L> {
+    fn from(inner: R) -> Self {
+        Self::new(inner)
+    }
+}
+
+impl<R: Scribe, L> Scribe for WithErrDecls<R, L> {
+    fn render(self, res: &mut salvo::prelude::Response) {
+        self.inner.render(res)
+    }
+}
+
+impl<R, L> EndpointOutRegister for WithErrDecls<R, L>
+where
+    R: EndpointOutRegister,
+    L: ErrDeclList,
+{
+    fn register(components: &mut Components, operation: &mut Operation) {
+        R::register(components, operation);
+        let schema = match operation.responses.get("200") {
+            Some(RefOr::Type(response)) => response
+                .contents
+                .get("application/json")
+                .and_then(|content| content.schema.clone()),
+            _ => None,
+        };
+        let mut groups: BTreeMap<u16, Vec<ErrDecl>> = BTreeMap::new();
+        for err_decl in L::err_decls() {
+            let status = StatusCode::from(err_decl.err_type).as_u16();
+            let group = groups.entry(status).or_default();
+            if !group.iter().any(|v| v.extract().code() == err_decl.extract().code()) {
+                group.push(err_decl);
+            }
+        }
+        for (status, err_decls) in groups {
+            let reason = StatusCode::from_u16(status)
+                .ok()
+                .and_then(|v| v.canonical_reason())
+                .unwrap_or("Error");
+            let mut content = Content::default().extend_examples(err_decls.iter().map(|err_decl| {
+                let brief = err_decl.extract();
+                let value =
+                    serde_json::to_value(ApiResponse::<(), ()>::from_error(brief.api_error())).unwrap_or_default();
+                let example = Example::new()
+                    .summary(brief.message())
+                    .description(err_decl.err_path.path())
+                    .value(value);
+                (brief.code().to_string(), example)
+            }));
+            content.schema = schema.clone();
+            let error_codes = err_decls.iter().map(err_decl_entry).collect();
+            let response = Response::new(reason)
+                .add_content("application/json", content)
+                .add_extension("x-error-codes", serde_json::Value::Array(error_codes));
+            operation.responses.insert(status.to_string(), response);
+        }
+    }
+}
+
+/// Documents every collected error declaration on an `OpenApi` document.
+pub trait OpenApiErrorCodes {
+    /// Add an `x-error-codes` extension listing the declarations collected by
+    /// `tally_err_decl()`, sorted by code.
+    fn with_error_codes(self) -> Self;
+}
+
+impl OpenApiErrorCodes for OpenApi {
+    fn with_error_codes(self) -> Self {
+        let mut err_decls = tally_err_decl().unique();
+        err_decls.sort_by_key(|v| (v.extract().code(), v.err_path.path()));
+        let error_codes = err_decls.iter().map(err_decl_entry).collect();
+        self.add_extension("x-error-codes", serde_json::Value::Array(error_codes))
+    }
+}
+
+fn err_decl_entry(err_decl: &ErrDecl) -> serde_json::Value {
+    let brief = err_decl.extract();
+    serde_json::json!({
+        "code": brief.code(),
+        "message": brief.message(),
+        "path": err_decl.err_path.path(),
+        "httpStatus": StatusCode::from(err_decl.err_type).as_u16(),
+    })
+}
+
+#[cfg(feature = "stream")]
+mod stream {
+    use futures_core::Stream;
+    use salvo::{
+        Scribe,
+        http::header::{CACHE_CONTROL, CONTENT_TYPE, HeaderValue},
+    };
+    use serde::Serialize;
+
+    use crate::stream::{NDJSON_CONTENT_TYPE, NdJsonStream, SSE_CONTENT_TYPE, SseStream, StreamFrame};
+
+    impl<F, T, Meta> Scribe for NdJsonStream<F>
+    where
+        F: Stream<Item = StreamFrame<T, Meta>> + Unpin + Send + 'static,
+        T: Serialize + 'static,
+        Meta: Serialize + 'static,
+    {
+        fn render(self, res: &mut salvo::prelude::Response) {
+            res.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(NDJSON_CONTENT_TYPE));
+            res.stream(self);
+        }
+    }
+
+    impl<F, T, Meta> Scribe for SseStream<F>
+    where
+        F: Stream<Item = StreamFrame<T, Meta>> + Unpin + Send + 'static,
+        T: Serialize + 'static,
+        Meta: Serialize + 'static,
+    {
+        fn render(self, res: &mut salvo::prelude::Response) {
+            res.headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(SSE_CONTENT_TYPE));
+            res.headers_mut()
+                .insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+            res.stream(self);
+        }
+    }
+}
+
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use salvo::prelude::*;
     use serde_json::{Value, json};
@@ -92,4 +470,78 @@ mod tests {
         });
         user.api_response_with_meta(DefaultMeta::new().with_request_id("abc-123"))
     }
+
+    const EP: error_code::ErrPath = error_code::ErrPathRoot::x::<0>("product")
+        .y::<1>("system")
+        .z::<20>("user");
+
+    struct GetUserErrors;
+    impl ErrDeclList for GetUserErrors {
+        fn err_decls() -> Vec<error_code::ErrDecl> {
+            vec![
+                ety_grpc::NOT_FOUND.declare(EP),
+                ety_grpc::INVALID_ARGUMENT.declare(EP),
+                (ety_grpc::INVALID_ARGUMENT | "Invalid email.").declare(EP.parent().z::<21>("email")),
+            ]
+        }
+    }
+
+    #[endpoint]
+    fn get_user_documented() -> WithErrDecls<ApiResponse<Value, DefaultMeta>, GetUserErrors> {
+        ety_grpc::NOT_FOUND
+            .declare(EP)
+            .api_error()
+            .api_response_without_meta()
+            .into()
+    }
+
+    #[test]
+    fn err_decl_responses() {
+        let router = Router::new().get(get_user_documented);
+        let doc = serde_json::to_value(OpenApi::new("test", "1").merge_router(&router).with_error_codes()).unwrap();
+        let responses = doc["paths"]["/"]["get"]["responses"].as_object().unwrap();
+        assert!(responses.contains_key("200"));
+        let not_found = &responses["404"];
+        assert_eq!("Not Found", not_found["description"]);
+        let example = &not_found["content"]["application/json"]["examples"]["1004000120"];
+        assert_eq!("Some requested entity was not found.", example["summary"]);
+        assert_eq!("X00(product)/Y01(system)/Z20(user)", example["description"]);
+        assert_eq!(2, responses["400"]["x-error-codes"].as_array().unwrap().len());
+        assert_eq!(
+            vec!["200", "400", "404"],
+            responses.keys().map(String::as_str).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            error_code::tally::tally_err_decl().unique().len(),
+            doc["x-error-codes"].as_array().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn one_of_schema() {
+        let router = Router::new().get(get_user);
+        let doc = serde_json::to_value(OpenApi::new("test", "1").merge_router(&router)).unwrap();
+        let schemas = doc["components"]["schemas"].as_object().unwrap();
+        let (_, api_response) = schemas
+            .iter()
+            .find(|(_, schema)| schema.get("oneOf").is_some())
+            .unwrap();
+        let one_of = api_response["oneOf"].as_array().unwrap();
+        assert_eq!(2, one_of.len());
+        let branch = |idx: usize| {
+            let location = one_of[idx]["$ref"].as_str().unwrap();
+            &schemas[location.trim_start_matches("#/components/schemas/")]
+        };
+        if cfg!(feature = "lite") {
+            assert!(api_response.get("discriminator").is_none());
+            assert_eq!(json!(["code", "data"]), branch(0)["required"]);
+            assert_eq!(json!(["code", "error"]), branch(1)["required"]);
+        } else {
+            assert_eq!("status", api_response["discriminator"]["propertyName"]);
+            assert_eq!(json!(["status", "data"]), branch(0)["required"]);
+            assert_eq!(json!(["status", "error"]), branch(1)["required"]);
+        }
+        assert!(branch(0)["properties"]["meta"].is_object());
+        assert!(branch(1)["properties"]["meta"].is_object());
+    }
 }
@@ -0,0 +1,187 @@
+use std::borrow::Cow;
+
+use schemars::{JsonSchema, Schema, SchemaGenerator, json_schema};
+
+use crate::{ApiResponse, ErrCode};
+
+/// A numeric or a symbolic code, see `CodeFormat`.
+impl JsonSchema for ErrCode {
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Borrowed("ErrCode")
+    }
+
+    fn json_schema(_generator: &mut SchemaGenerator) -> Schema {
+        json_schema!({
+            "oneOf": [
+                { "type": "integer", "format": "uint64", "minimum": 0 },
+                { "type": "string" },
+            ]
+        })
+    }
+}
+
+impl<Data, Meta> JsonSchema for ApiResponse<Data, Meta>
+where
+    Data: JsonSchema,
+    Meta: JsonSchema,
+{
+    fn schema_name() -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "ApiResponse_for_{}_and_{}",
+            Data::schema_name(),
+            Meta::schema_name()
+        ))
+    }
+
+    fn schema_id() -> Cow<'static, str> {
+        Cow::Owned(format!(
+            "{}::ApiResponse<{}, {}>",
+            module_path!(),
+            Data::schema_id(),
+            Meta::schema_id()
+        ))
+    }
+
+    /// A `oneOf` over the success and error shapes, discriminated by a `const`
+    /// `status` (or `code == 0` in lite mode).
+    fn json_schema(generator: &mut SchemaGenerator) -> Schema {
+        let data = generator.subschema_for::<Data>();
+        let meta = generator.subschema_for::<Meta>();
+        #[cfg(not(feature = "lite"))]
+        {
+            let error = generator.subschema_for::<crate::ApiError>();
+            json_schema!({
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": {
+                            "status": { "const": "success" },
+                            "data": data,
+                            "meta": meta,
+                        },
+                        "required": ["status", "data"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "status": { "const": "error" },
+                            "error": error,
+                            "meta": meta,
+                        },
+                        "required": ["status", "error"],
+                        "additionalProperties": false,
+                    },
+                ]
+            })
+        }
+        #[cfg(feature = "lite")]
+        {
+            let error = generator.subschema_for::<crate::lite::__ApiError>();
+            json_schema!({
+                "oneOf": [
+                    {
+                        "type": "object",
+                        "properties": {
+                            "code": { "const": 0 },
+                            "data": data,
+                            "meta": meta,
+                        },
+                        "required": ["code", "data"],
+                        "additionalProperties": false,
+                    },
+                    {
+                        "type": "object",
+                        "properties": {
+                            "code": {
+                                "oneOf": [
+                                    { "type": "integer", "format": "uint64", "minimum": 1 },
+                                    { "type": "string", "minLength": 1 },
+                                ],
+                            },
+                            "error": error,
+                            "meta": meta,
+                        },
+                        "required": ["code", "error"],
+                        "additionalProperties": false,
+                    },
+                ]
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use schemars::{SchemaGenerator, generate::SchemaSettings};
+    use serde_json::Value;
+
+    use crate::{ApiError, ApiResponse, DefaultMeta, Pagination};
+
+    /// Returns the indexes of the `oneOf` branches of the root `schema` that
+    /// validate `instance`.
+    fn matching_branches(schema: &Value, instance: &Value) -> Vec<usize> {
+        let branches = schema["oneOf"].as_array().unwrap();
+        (0..branches.len())
+            .filter(|idx| {
+                let mut branch = branches[*idx].clone();
+                branch["$defs"] = schema["$defs"].clone();
+                jsonschema::draft202012::is_valid(&branch, instance)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn one_of_matches_serializer() {
+        let mut generator = SchemaGenerator::new(SchemaSettings::draft2020_12());
+        let schema = generator
+            .root_schema_for::<ApiResponse<String, DefaultMeta>>()
+            .to_value();
+        let validator = jsonschema::draft202012::new(&schema).unwrap();
+
+        let meta = DefaultMeta::new()
+            .with_request_id("abc-123")
+            .with_pagination(Some(Pagination::default().with_current_page(1)));
+        let success =
+            serde_json::to_value(ApiResponse::<_, DefaultMeta>::new_success("data".to_owned(), meta)).unwrap();
+        let success_without_meta =
+            serde_json::to_value(ApiResponse::<_, DefaultMeta>::from_success_data("data".to_owned())).unwrap();
+        let error = serde_json::to_value(ApiResponse::<String, DefaultMeta>::from_error(
+            ApiError::new(404u32, "not found").with_detail("key", "value"),
+        ))
+        .unwrap();
+
+        for instance in [&success, &success_without_meta, &error] {
+            assert!(validator.is_valid(instance), "{instance}");
+        }
+        assert_eq!(vec![0], matching_branches(&schema, &success));
+        assert_eq!(vec![0], matching_branches(&schema, &success_without_meta));
+        assert_eq!(vec![1], matching_branches(&schema, &error));
+
+        let mut bad_meta = success.clone();
+        bad_meta["meta"]["pagination"]["currentPage"] = Value::from("1");
+        assert!(!validator.is_valid(&bad_meta));
+        let mut mixed = error.clone();
+        mixed["data"] = Value::from("data");
+        assert!(!validator.is_valid(&mixed));
+
+        let defs = generator.definitions();
+        assert!(defs.contains_key("DefaultMeta"));
+        assert!(defs.contains_key("Pagination"));
+        assert!(defs.contains_key("RateLimit"));
+        let error_def = if cfg!(feature = "lite") {
+            "__ApiError"
+        } else {
+            "ApiError"
+        };
+        let error_props = defs[error_def]["properties"].as_object().unwrap();
+        let mut error_fields: Vec<_> = error_props.keys().map(String::as_str).collect();
+        error_fields.sort_unstable();
+        if cfg!(feature = "lite") {
+            assert_eq!(vec!["details", "message"], error_fields);
+        } else {
+            assert_eq!(vec!["code", "details", "message"], error_fields);
+        }
+    }
+}
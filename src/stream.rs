@@ -0,0 +1,776 @@
+//! Streaming encodings of a sequence of `ApiResponse` frames.
+//!
+//! A `Stream<Item = Result<T, ApiError>>` is turned into a sequence of
+//! envelopes whose `data` is a [`StreamChunk`]:
+//!
+//! * every `Ok(item)` becomes a success frame carrying `{"item": ...}`;
+//! * the first frame carries the `meta` header, if one was given;
+//! * the stream is terminated either by a success frame carrying `{"end":
+//!   {"count": n}}`, or by an error frame when the source yields an `Err`.
+//!
+//! The frames can be encoded as newline-delimited JSON ([`NdJsonStream`]) or
+//! as Server-Sent Events ([`SseStream`]), and decoded again on the client side
+//! with [`NdJsonDecoder`], [`SseDecoder`] or [`DecodeStream`].
+
+use std::{
+    error::Error,
+    fmt,
+    marker::PhantomData,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{ApiError, ApiResponse, ErrorResponse, SuccessResponse};
+
+/// Content type of newline-delimited JSON.
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+/// Content type of Server-Sent Events.
+pub const SSE_CONTENT_TYPE: &str = "text/event-stream";
+
+/// The `data` payload of a single frame in a streamed response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+#[non_exhaustive]
+pub enum StreamChunk<T> {
+    Item(T),
+    End(StreamEnd),
+}
+
+/// Summary carried by the terminating success frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct StreamEnd {
+    /// The number of items that were sent before the terminating frame.
+    pub count: u64,
+}
+
+/// A single frame of a streamed response.
+pub type StreamFrame<T, Meta> = ApiResponse<StreamChunk<T>, Meta>;
+
+impl<T> StreamChunk<T> {
+    pub const fn is_item(&self) -> bool {
+        matches!(self, Self::Item(_))
+    }
+    pub const fn is_end(&self) -> bool {
+        matches!(self, Self::End(_))
+    }
+    pub fn into_item(self) -> Option<T> {
+        match self {
+            StreamChunk::Item(item) => Some(item),
+            StreamChunk::End(_) => None,
+        }
+    }
+}
+
+impl<T, Meta> ApiResponse<StreamChunk<T>, Meta> {
+    /// Returns `true` if this is the last frame of a stream.
+    pub const fn is_terminal(&self) -> bool {
+        match self {
+            ApiResponse::Success(success_response) => success_response.data.is_end(),
+            ApiResponse::Error(_) => true,
+        }
+    }
+    /// The SSE event name of the frame: `item`, `end` or `error`.
+    pub const fn event_name(&self) -> &'static str {
+        match self {
+            ApiResponse::Success(SuccessResponse {
+                data: StreamChunk::Item(_),
+                ..
+            }) => "item",
+            ApiResponse::Success(SuccessResponse {
+                data: StreamChunk::End(_),
+                ..
+            }) => "end",
+            ApiResponse::Error(_) => "error",
+        }
+    }
+}
+
+/// Adapts a `Stream<Item = Result<T, ApiError>>` into a stream of
+/// [`StreamFrame`]s.
+#[must_use = "streams do nothing unless polled"]
+pub struct ApiResponseStream<S, Meta> {
+    inner: Pin<Box<S>>,
+    meta: Option<Meta>,
+    count: u64,
+    done: bool,
+}
+
+impl<S, Meta> ApiResponseStream<S, Meta> {
+    #[inline]
+    pub fn new(stream: S) -> Self {
+        Self {
+            inner: Box::pin(stream),
+            meta: None,
+            count: 0,
+            done: false,
+        }
+    }
+    /// Set the meta header carried by the first frame.
+    #[inline]
+    pub fn with_meta(mut self, meta: Meta) -> Self {
+        self.meta = Some(meta);
+        self
+    }
+    /// Encode the frames as newline-delimited JSON.
+    #[inline]
+    pub const fn ndjson(self) -> NdJsonStream<Self> {
+        NdJsonStream::new(self)
+    }
+    /// Encode the frames as Server-Sent Events.
+    #[inline]
+    pub const fn sse(self) -> SseStream<Self> {
+        SseStream::new(self)
+    }
+}
+
+impl<S, Meta> fmt::Debug for ApiResponseStream<S, Meta> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiResponseStream")
+            .field("count", &self.count)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, T, Meta> Stream for ApiResponseStream<S, Meta>
+where
+    S: Stream<Item = Result<T, ApiError>>,
+    Meta: Unpin,
+{
+    type Item = StreamFrame<T, Meta>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        let next = match self.inner.as_mut().poll_next(cx) {
+            Poll::Pending => return Poll::Pending,
+            Poll::Ready(next) => next,
+        };
+        let meta = self.meta.take();
+        let frame = match next {
+            Some(Ok(item)) => {
+                self.count = self.count.saturating_add(1);
+                ApiResponse::Success(SuccessResponse {
+                    data: StreamChunk::Item(item),
+                    meta,
+                })
+            }
+            Some(Err(error)) => {
+                self.done = true;
+                ApiResponse::Error(ErrorResponse { error, meta })
+            }
+            None => {
+                self.done = true;
+                ApiResponse::Success(SuccessResponse {
+                    data: StreamChunk::End(StreamEnd { count: self.count }),
+                    meta,
+                })
+            }
+        };
+        Poll::Ready(Some(frame))
+    }
+}
+
+/// Newline-delimited JSON encoding of a stream of [`StreamFrame`]s.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct NdJsonStream<F> {
+    frames: F,
+}
+
+impl<F> NdJsonStream<F> {
+    #[inline]
+    pub const fn new(frames: F) -> Self {
+        Self { frames }
+    }
+}
+
+impl<F, T, Meta> Stream for NdJsonStream<F>
+where
+    F: Stream<Item = StreamFrame<T, Meta>> + Unpin,
+    T: Serialize,
+    Meta: Serialize,
+{
+    type Item = Result<String, serde_json::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.frames).poll_next(cx).map(|next| {
+            next.map(|frame| {
+                let mut line = serde_json::to_string(&frame)?;
+                line.push('\n');
+                Ok(line)
+            })
+        })
+    }
+}
+
+/// Server-Sent Events encoding of a stream of [`StreamFrame`]s.
+///
+/// Each frame is sent as one event named after [`StreamFrame::event_name`],
+/// with the JSON envelope as its `data`.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct SseStream<F> {
+    frames: F,
+}
+
+impl<F> SseStream<F> {
+    #[inline]
+    pub const fn new(frames: F) -> Self {
+        Self { frames }
+    }
+}
+
+impl<F, T, Meta> Stream for SseStream<F>
+where
+    F: Stream<Item = StreamFrame<T, Meta>> + Unpin,
+    T: Serialize,
+    Meta: Serialize,
+{
+    type Item = Result<String, serde_json::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.frames).poll_next(cx).map(|next| {
+            next.map(|frame| {
+                let data = serde_json::to_string(&frame)?;
+                Ok(format!("event: {}\ndata: {data}\n\n", frame.event_name()))
+            })
+        })
+    }
+}
+
+/// Incremental decoder of newline-delimited JSON frames.
+#[derive(Debug)]
+pub struct NdJsonDecoder<T, Meta> {
+    buf: Vec<u8>,
+    /// The error of a line following frames already returned.
+    error: Option<serde_json::Error>,
+    marker: PhantomData<fn() -> StreamFrame<T, Meta>>,
+}
+
+impl<T, Meta> Default for NdJsonDecoder<T, Meta> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Meta> NdJsonDecoder<T, Meta> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            error: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, Meta> NdJsonDecoder<T, Meta>
+where
+    T: DeserializeOwned,
+    Meta: DeserializeOwned,
+{
+    /// Feed a chunk of bytes and return the frames completed by it.
+    ///
+    /// When a line fails to decode after frames of the same chunk, those
+    /// frames are returned first and the error on the next call.
+    pub fn decode(&mut self, chunk: &[u8]) -> Result<Vec<StreamFrame<T, Meta>>, serde_json::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.buf.extend_from_slice(chunk);
+        let mut frames = Vec::new();
+        while let Some(pos) = self.buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buf.drain(..=pos).collect();
+            match Self::decode_line(&line) {
+                Ok(frame) => frames.extend(frame),
+                Err(e) => return defer_error(&mut self.error, frames, e),
+            }
+        }
+        Ok(frames)
+    }
+    /// Decode whatever is left in the buffer once the body has ended.
+    pub fn finish(&mut self) -> Result<Option<StreamFrame<T, Meta>>, serde_json::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        let line = std::mem::take(&mut self.buf);
+        Self::decode_line(&line)
+    }
+    fn decode_line(line: &[u8]) -> Result<Option<StreamFrame<T, Meta>>, serde_json::Error> {
+        if line.iter().all(u8::is_ascii_whitespace) {
+            return Ok(None);
+        }
+        serde_json::from_slice(line).map(Some)
+    }
+}
+
+/// Incremental decoder of Server-Sent Events frames.
+///
+/// Only the `data` field of each event is used; comments, `event`, `id` and
+/// `retry` fields are ignored.
+#[derive(Debug)]
+pub struct SseDecoder<T, Meta> {
+    buf: Vec<u8>,
+    data: Option<Vec<u8>>,
+    /// Whether the last line ended with `\r`, whose `\n` may start the next
+    /// chunk.
+    after_cr: bool,
+    /// The error of an event following frames already returned.
+    error: Option<serde_json::Error>,
+    marker: PhantomData<fn() -> StreamFrame<T, Meta>>,
+}
+
+impl<T, Meta> Default for SseDecoder<T, Meta> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, Meta> SseDecoder<T, Meta> {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            buf: Vec::new(),
+            data: None,
+            after_cr: false,
+            error: None,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, Meta> SseDecoder<T, Meta>
+where
+    T: DeserializeOwned,
+    Meta: DeserializeOwned,
+{
+    /// Feed a chunk of bytes and return the frames completed by it.
+    ///
+    /// Lines end with `\r\n`, `\n` or `\r`. The bytes are buffered until an
+    /// event is complete, so a character split across chunks is kept whole,
+    /// and invalid UTF-8 makes the event fail to decode. When an event fails
+    /// to decode after frames of the same chunk, those frames are returned
+    /// first and the error on the next call.
+    pub fn decode(&mut self, chunk: &[u8]) -> Result<Vec<StreamFrame<T, Meta>>, serde_json::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        self.buf.extend_from_slice(chunk);
+        let mut frames = Vec::new();
+        loop {
+            if self.after_cr && !self.buf.is_empty() {
+                self.after_cr = false;
+                if self.buf.first() == Some(&b'\n') {
+                    self.buf.remove(0);
+                }
+            }
+            let Some(pos) = self.buf.iter().position(|b| matches!(b, b'\n' | b'\r')) else {
+                break;
+            };
+            self.after_cr = self.buf.get(pos) == Some(&b'\r');
+            let mut line: Vec<u8> = self.buf.drain(..=pos).collect();
+            line.pop();
+            match self.decode_line(&line) {
+                Ok(frame) => frames.extend(frame),
+                Err(e) => return defer_error(&mut self.error, frames, e),
+            }
+        }
+        Ok(frames)
+    }
+    /// Dispatch the pending event, if any, once the body has ended.
+    pub fn finish(&mut self) -> Result<Option<StreamFrame<T, Meta>>, serde_json::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        let line = std::mem::take(&mut self.buf);
+        if let Some(frame) = self.decode_line(&line)? {
+            return Ok(Some(frame));
+        }
+        self.decode_line(&[])
+    }
+    fn decode_line(&mut self, line: &[u8]) -> Result<Option<StreamFrame<T, Meta>>, serde_json::Error> {
+        if line.is_empty() {
+            return match self.data.take() {
+                Some(data) => serde_json::from_slice(&data).map(Some),
+                None => Ok(None),
+            };
+        }
+        let (field, raw_value) = match line.iter().position(|b| *b == b':') {
+            Some(pos) => (&line[..pos], &line[pos + 1..]),
+            None => (line, &[][..]),
+        };
+        if field == b"data" {
+            let value = raw_value.strip_prefix(b" ").unwrap_or(raw_value);
+            match &mut self.data {
+                Some(data) => {
+                    data.push(b'\n');
+                    data.extend_from_slice(value);
+                }
+                None => self.data = Some(value.to_vec()),
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Return the frames decoded before `e`, if any, and keep `e` for the next
+/// call.
+fn defer_error<F>(
+    slot: &mut Option<serde_json::Error>,
+    frames: Vec<F>,
+    e: serde_json::Error,
+) -> Result<Vec<F>, serde_json::Error> {
+    if frames.is_empty() {
+        return Err(e);
+    }
+    *slot = Some(e);
+    Ok(frames)
+}
+
+/// A decoder of a byte stream into [`StreamFrame`]s.
+pub trait FrameDecoder {
+    type Frame;
+    fn decode(&mut self, chunk: &[u8]) -> Result<Vec<Self::Frame>, serde_json::Error>;
+    fn finish(&mut self) -> Result<Option<Self::Frame>, serde_json::Error>;
+}
+
+impl<T: DeserializeOwned, Meta: DeserializeOwned> FrameDecoder for NdJsonDecoder<T, Meta> {
+    type Frame = StreamFrame<T, Meta>;
+
+    #[inline]
+    fn decode(&mut self, chunk: &[u8]) -> Result<Vec<Self::Frame>, serde_json::Error> {
+        NdJsonDecoder::decode(self, chunk)
+    }
+    #[inline]
+    fn finish(&mut self) -> Result<Option<Self::Frame>, serde_json::Error> {
+        NdJsonDecoder::finish(self)
+    }
+}
+
+impl<T: DeserializeOwned, Meta: DeserializeOwned> FrameDecoder for SseDecoder<T, Meta> {
+    type Frame = StreamFrame<T, Meta>;
+
+    #[inline]
+    fn decode(&mut self, chunk: &[u8]) -> Result<Vec<Self::Frame>, serde_json::Error> {
+        SseDecoder::decode(self, chunk)
+    }
+    #[inline]
+    fn finish(&mut self) -> Result<Option<Self::Frame>, serde_json::Error> {
+        SseDecoder::finish(self)
+    }
+}
+
+/// An error raised while decoding a streamed response.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The underlying body stream failed.
+    Body(Box<dyn Error + Send + Sync + 'static>),
+    /// A frame was not a valid envelope.
+    Json(serde_json::Error),
+    /// The body ended without a terminating frame.
+    Truncated,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::Body(e) => write!(f, "stream body error: {e}"),
+            DecodeError::Json(e) => write!(f, "invalid stream frame: {e}"),
+            DecodeError::Truncated => f.write_str("stream ended without a terminating frame"),
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecodeError::Body(e) => Some(e.as_ref()),
+            DecodeError::Json(e) => Some(e),
+            DecodeError::Truncated => None,
+        }
+    }
+}
+
+/// Decodes a stream of byte chunks into [`StreamFrame`]s.
+#[must_use = "streams do nothing unless polled"]
+pub struct DecodeStream<B, D: FrameDecoder> {
+    body: Pin<Box<B>>,
+    decoder: D,
+    pending: std::collections::VecDeque<D::Frame>,
+    terminated: bool,
+    /// The body has ended; the stream is truncated unless the last frame is
+    /// terminal.
+    ended: bool,
+    done: bool,
+}
+
+impl<B, T, Meta> DecodeStream<B, NdJsonDecoder<T, Meta>>
+where
+    T: DeserializeOwned,
+    Meta: DeserializeOwned,
+{
+    #[inline]
+    pub fn ndjson(body: B) -> Self {
+        Self::new(body, NdJsonDecoder::new())
+    }
+}
+
+impl<B, T, Meta> DecodeStream<B, SseDecoder<T, Meta>>
+where
+    T: DeserializeOwned,
+    Meta: DeserializeOwned,
+{
+    #[inline]
+    pub fn sse(body: B) -> Self {
+        Self::new(body, SseDecoder::new())
+    }
+}
+
+impl<B, D: FrameDecoder> DecodeStream<B, D> {
+    #[inline]
+    pub fn new(body: B, decoder: D) -> Self {
+        Self {
+            body: Box::pin(body),
+            decoder,
+            pending: std::collections::VecDeque::new(),
+            terminated: false,
+            ended: false,
+            done: false,
+        }
+    }
+}
+
+impl<B, D: FrameDecoder> fmt::Debug for DecodeStream<B, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecodeStream")
+            .field("terminated", &self.terminated)
+            .field("done", &self.done)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<B, C, E, T, Meta, D> Stream for DecodeStream<B, D>
+where
+    B: Stream<Item = Result<C, E>>,
+    C: AsRef<[u8]>,
+    E: Into<Box<dyn Error + Send + Sync + 'static>>,
+    D: FrameDecoder<Frame = StreamFrame<T, Meta>> + Unpin,
+    D::Frame: Unpin,
+{
+    type Item = Result<StreamFrame<T, Meta>, DecodeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(frame) = self.pending.pop_front() {
+                self.terminated = frame.is_terminal();
+                return Poll::Ready(Some(Ok(frame)));
+            }
+            if self.done {
+                if std::mem::take(&mut self.ended) && !self.terminated {
+                    return Poll::Ready(Some(Err(DecodeError::Truncated)));
+                }
+                return Poll::Ready(None);
+            }
+            match self.body.as_mut().poll_next(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(Some(Ok(chunk))) => match self.decoder.decode(chunk.as_ref()) {
+                    Ok(frames) => self.pending.extend(frames),
+                    Err(e) => {
+                        self.done = true;
+                        return Poll::Ready(Some(Err(DecodeError::Json(e))));
+                    }
+                },
+                Poll::Ready(Some(Err(e))) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(DecodeError::Body(e.into()))));
+                }
+                Poll::Ready(None) => {
+                    self.done = true;
+                    match self.decoder.finish() {
+                        Ok(frame) => {
+                            self.pending.extend(frame);
+                            self.ended = true;
+                        }
+                        Err(e) => return Poll::Ready(Some(Err(DecodeError::Json(e)))),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::shadow_unrelated)]
+mod tests {
+    use futures_util::{StreamExt, stream};
+
+    use super::{ApiResponseStream, DecodeError, DecodeStream, NdJsonDecoder, SseDecoder, StreamChunk, StreamFrame};
+    use crate::{ApiError, DefaultMeta, SuccessResponse};
+
+    fn source(fail: bool) -> impl futures_core::Stream<Item = Result<u32, ApiError>> {
+        let mut items = vec![Ok(1), Ok(2)];
+        if fail {
+            items.push(Err(ApiError::new(404u32, "not found")));
+            items.push(Ok(3));
+        }
+        stream::iter(items)
+    }
+
+    #[tokio::test]
+    async fn ndjson() {
+        let body: Vec<String> = ApiResponseStream::new(source(false))
+            .with_meta(DefaultMeta::new().with_request_id("abc-123"))
+            .ndjson()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        let expected = if cfg!(feature = "lite") {
+            [
+                "{\"code\":0,\"data\":{\"item\":1},\"meta\":{\"requestId\":\"abc-123\"}}\n",
+                "{\"code\":0,\"data\":{\"item\":2}}\n",
+                "{\"code\":0,\"data\":{\"end\":{\"count\":2}}}\n",
+            ]
+        } else {
+            [
+                "{\"status\":\"success\",\"data\":{\"item\":1},\"meta\":{\"requestId\":\"abc-123\"}}\n",
+                "{\"status\":\"success\",\"data\":{\"item\":2}}\n",
+                "{\"status\":\"success\",\"data\":{\"end\":{\"count\":2}}}\n",
+            ]
+        };
+        assert_eq!(expected.as_slice(), body.as_slice());
+
+        let mut decoder = NdJsonDecoder::<u32, DefaultMeta>::new();
+        let joined = body.concat();
+        let (head, tail) = joined.as_bytes().split_at(10);
+        let mut frames = decoder.decode(head).unwrap();
+        frames.extend(decoder.decode(tail).unwrap());
+        assert!(decoder.finish().unwrap().is_none());
+        assert_eq!(3, frames.len());
+        assert_eq!("abc-123", frames[0].get_meta().unwrap().request_id().unwrap().as_str());
+        assert!(frames[2].is_terminal());
+    }
+
+    #[tokio::test]
+    async fn sse_error_terminates() {
+        let body: Vec<String> = ApiResponseStream::<_, ()>::new(source(true))
+            .sse()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        assert_eq!(3, body.len());
+        assert!(body[0].starts_with("event: item\ndata: "));
+        assert!(body[2].starts_with("event: error\ndata: "));
+
+        let mut decoder = SseDecoder::<u32, ()>::new();
+        let frames: Vec<StreamFrame<u32, ()>> = body
+            .iter()
+            .flat_map(|chunk| decoder.decode(chunk.as_bytes()).unwrap())
+            .collect();
+        assert_eq!(3, frames.len());
+        assert!(matches!(
+            &frames[1],
+            StreamFrame::Success(SuccessResponse {
+                data: StreamChunk::Item(2),
+                ..
+            })
+        ));
+        assert!(matches!(&frames[2], StreamFrame::Error(e) if e.code() == 404));
+    }
+
+    #[tokio::test]
+    async fn sse_split_chunks() {
+        let body = ApiResponseStream::<_, ()>::new(stream::iter([Ok::<_, ApiError>("é".to_owned())]))
+            .sse()
+            .map(Result::unwrap)
+            .collect::<Vec<String>>()
+            .await
+            .concat();
+        let split = body.find('é').unwrap() + 1;
+        let (head, tail) = body.as_bytes().split_at(split);
+        let mut decoder = SseDecoder::<String, ()>::new();
+        let mut frames = decoder.decode(head).unwrap();
+        frames.extend(decoder.decode(tail).unwrap());
+        assert_eq!(2, frames.len());
+        assert!(matches!(
+            &frames[0],
+            StreamFrame::Success(SuccessResponse {
+                data: StreamChunk::Item(item),
+                ..
+            }) if item == "é"
+        ));
+
+        for ending in ["\r", "\r\n"] {
+            let mut decoder = SseDecoder::<String, ()>::new();
+            let frames: Vec<_> = body
+                .replace('\n', ending)
+                .as_bytes()
+                .iter()
+                .flat_map(|byte| decoder.decode(std::slice::from_ref(byte)).unwrap())
+                .collect();
+            assert_eq!(2, frames.len());
+            assert!(decoder.finish().unwrap().is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn decode_stream() {
+        let body = ApiResponseStream::<_, ()>::new(source(false)).ndjson();
+        let frames: Vec<_> = DecodeStream::<_, NdJsonDecoder<u32, ()>>::ndjson(body).collect().await;
+        assert_eq!(3, frames.len());
+        assert!(frames.iter().all(Result::is_ok));
+
+        let mut lines: Vec<_> = ApiResponseStream::<_, ()>::new(source(false))
+            .ndjson()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        lines.pop();
+        let truncated = stream::iter(lines.into_iter().map(Ok::<_, std::io::Error>));
+        let frames: Vec<_> = DecodeStream::<_, NdJsonDecoder<u32, ()>>::ndjson(truncated)
+            .collect()
+            .await;
+        assert!(matches!(frames.last(), Some(Err(DecodeError::Truncated))));
+
+        let mut lines: Vec<_> = ApiResponseStream::<_, ()>::new(source(false))
+            .ndjson()
+            .map(Result::unwrap)
+            .collect()
+            .await;
+        lines.pop();
+        let unterminated = lines.concat().trim_end().to_owned();
+        let frames: Vec<_> =
+            DecodeStream::<_, NdJsonDecoder<u32, ()>>::ndjson(stream::iter([Ok::<_, std::io::Error>(unterminated)]))
+                .collect()
+                .await;
+        assert_eq!(3, frames.len());
+        assert!(frames[..2].iter().all(Result::is_ok));
+        assert!(matches!(frames[2], Err(DecodeError::Truncated)));
+    }
+
+    #[test]
+    fn decode_error_after_frames() {
+        let mut decoder = NdJsonDecoder::<u32, ()>::new();
+        let chunk = if cfg!(feature = "lite") {
+            "{\"code\":0,\"data\":{\"item\":1}}\nnot json\n{\"code\":0,\"data\":{\"item\":2}}\n"
+        } else {
+            "{\"status\":\"success\",\"data\":{\"item\":1}}\nnot json\n{\"status\":\"success\",\"data\":{\"item\":2}}\n"
+        };
+        assert_eq!(1, decoder.decode(chunk.as_bytes()).unwrap().len());
+        assert!(decoder.decode(b"").is_err());
+
+        let mut decoder = SseDecoder::<u32, ()>::new();
+        let events = chunk
+            .lines()
+            .map(|line| format!("data: {line}\n\n"))
+            .collect::<String>();
+        assert_eq!(1, decoder.decode(events.as_bytes()).unwrap().len());
+        assert!(decoder.finish().is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use serde::{Deserialize, Serialize};
 
-use crate::ApiResponse;
+use crate::{
+    ApiResponse,
+    meta::{ResponseMeta, merge_meta},
+};
 
 /// Struct to represent a successful response
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
@@ -31,6 +34,14 @@ impl<Data, Meta> SuccessResponse<Data, Meta> {
         self.meta = Some(meta);
         self
     }
+    /// Merge `meta` into the meta, see [`ResponseMeta::merge`].
+    pub fn merge_meta(&mut self, meta: Meta) -> &mut Self
+    where
+        Meta: ResponseMeta,
+    {
+        merge_meta(&mut self.meta, meta);
+        self
+    }
 }
 
 pub trait ApiSuccessResponse: Sized {
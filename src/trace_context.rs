@@ -0,0 +1,192 @@
+//! W3C trace context: the `traceparent` header and the `traceId`, `spanId`
+//! and `traceFlags` fields of `DefaultMeta`, so that the errors reported by
+//! clients can be joined to the distributed traces.
+//!
+//! With the `opentelemetry` feature, `TraceParent::current` and
+//! `DefaultMeta::with_current_trace` read the ids from the current
+//! OpenTelemetry context, i.e. the one attached by the OpenTelemetry
+//! instrumentation of the request.
+
+use std::{fmt, str::FromStr};
+
+use crate::DefaultMeta;
+
+/// A W3C `traceparent`: `00-<trace-id>-<parent-id>-<trace-flags>`.
+///
+/// ```
+/// use api_response::prelude::*;
+///
+/// let parent: TraceParent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".parse().unwrap();
+/// assert!(parent.sampled());
+/// let meta = DefaultMeta::new().with_trace_parent(parent);
+/// assert_eq!(Some("00f067aa0ba902b7"), meta.span_id().map(String::as_str));
+/// assert_eq!(Some(parent), meta.trace_parent());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct TraceParent {
+    pub trace_id: u128,
+    /// The id of the span of the caller, or of the current span when
+    /// emitted.
+    pub span_id: u64,
+    pub flags: u8,
+}
+
+/// The value is not a valid version `00` `traceparent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct InvalidTraceParent;
+
+impl fmt::Display for InvalidTraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid traceparent")
+    }
+}
+
+impl std::error::Error for InvalidTraceParent {}
+
+impl TraceParent {
+    /// The `sampled` trace flag.
+    pub const SAMPLED: u8 = 0x01;
+
+    /// A trace parent with no flag set.
+    #[inline]
+    pub const fn new(trace_id: u128, span_id: u64) -> Self {
+        Self {
+            trace_id,
+            span_id,
+            flags: 0,
+        }
+    }
+    #[inline]
+    pub const fn with_flags(mut self, flags: u8) -> Self {
+        self.flags = flags;
+        self
+    }
+    #[inline]
+    pub const fn sampled(&self) -> bool {
+        self.flags & Self::SAMPLED != 0
+    }
+    /// Whether neither id is all zeroes.
+    #[inline]
+    pub const fn is_valid(&self) -> bool {
+        self.trace_id != 0 && self.span_id != 0
+    }
+    /// The trace id as 32 lowercase hex digits.
+    pub fn trace_id_hex(&self) -> String {
+        format!("{:032x}", self.trace_id)
+    }
+    /// The span id as 16 lowercase hex digits.
+    pub fn span_id_hex(&self) -> String {
+        format!("{:016x}", self.span_id)
+    }
+    /// Build a trace parent from hex ids, e.g. the `traceId` and `spanId` of
+    /// a meta.
+    pub fn from_hex(trace_id: &str, span_id: &str) -> Result<Self, InvalidTraceParent> {
+        let parent = Self::new(
+            parse_hex(trace_id, 32)?,
+            parse_hex(span_id, 16)?.try_into().map_err(|_| InvalidTraceParent)?,
+        );
+        if parent.is_valid() {
+            Ok(parent)
+        } else {
+            Err(InvalidTraceParent)
+        }
+    }
+
+    /// The span context of the current OpenTelemetry context, if valid.
+    #[cfg(feature = "opentelemetry")]
+    pub fn current() -> Option<Self> {
+        use opentelemetry::trace::TraceContextExt;
+        let context = opentelemetry::Context::current();
+        let span = context.span();
+        let span_context = span.span_context();
+        span_context.is_valid().then(|| Self {
+            trace_id: u128::from_be_bytes(span_context.trace_id().to_bytes()),
+            span_id: u64::from_be_bytes(span_context.span_id().to_bytes()),
+            flags: span_context.trace_flags().to_u8(),
+        })
+    }
+}
+
+fn parse_hex(hex: &str, digits: usize) -> Result<u128, InvalidTraceParent> {
+    if hex.len() != digits || !hex.bytes().all(|v| v.is_ascii_digit() || (b'a'..=b'f').contains(&v)) {
+        return Err(InvalidTraceParent);
+    }
+    u128::from_str_radix(hex, 16).map_err(|_| InvalidTraceParent)
+}
+
+impl FromStr for TraceParent {
+    type Err = InvalidTraceParent;
+
+    /// Parse a version `00` `traceparent`; the ids must not be all zeroes.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().split('-');
+        let (Some("00"), Some(trace_id), Some(span_id), Some(flags_hex), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(InvalidTraceParent);
+        };
+        let flags = u8::try_from(parse_hex(flags_hex, 2)?).map_err(|_| InvalidTraceParent)?;
+        Ok(Self::from_hex(trace_id, span_id)?.with_flags(flags))
+    }
+}
+
+impl fmt::Display for TraceParent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "00-{:032x}-{:016x}-{:02x}", self.trace_id, self.span_id, self.flags)
+    }
+}
+
+impl DefaultMeta {
+    /// Set the `traceId`, `spanId` and `traceFlags`.
+    #[inline]
+    pub fn with_trace_parent(mut self, parent: TraceParent) -> Self {
+        self.trace_id = Some(parent.trace_id_hex());
+        self.span_id = Some(parent.span_id_hex());
+        self.trace_flags = Some(parent.flags);
+        self
+    }
+    /// The trace parent of the `traceId`, `spanId` and `traceFlags`, if both
+    /// ids are valid; no flag is set when `traceFlags` is unset.
+    pub fn trace_parent(&self) -> Option<TraceParent> {
+        let parent = TraceParent::from_hex(self.trace_id.as_deref()?, self.span_id.as_deref()?).ok()?;
+        Some(parent.with_flags(self.trace_flags.unwrap_or_default()))
+    }
+    /// Set the `traceId`, `spanId` and `traceFlags` from the current
+    /// OpenTelemetry context, unless the `traceId` is already set.
+    #[cfg(feature = "opentelemetry")]
+    pub fn with_current_trace(self) -> Self {
+        match TraceParent::current() {
+            Some(parent) if self.trace_id.is_none() => self.with_trace_parent(parent),
+            _ => self,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::{InvalidTraceParent, TraceParent};
+
+    #[test]
+    fn parse_and_emit() {
+        let value = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        let parent: TraceParent = value.parse().unwrap();
+        assert_eq!(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736, parent.trace_id);
+        assert_eq!(0x00f0_67aa_0ba9_02b7, parent.span_id);
+        assert!(parent.sampled());
+        assert_eq!(value, parent.to_string());
+        for invalid in [
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-1",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-00",
+            "00-+bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(Err(InvalidTraceParent), invalid.parse::<TraceParent>(), "{invalid}");
+        }
+    }
+}
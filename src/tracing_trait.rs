@@ -0,0 +1,113 @@
+use std::{error::Error, fmt::Write};
+
+use tracing::{Level, Span};
+
+use crate::{
+    ApiError, ErrorResponse, ResponseMeta,
+    error_code::{DefaultCodeLayout, Severity},
+};
+
+/// The field of the current span the request id is recorded in.
+///
+/// `tracing` only records the fields a span declared when it was created, so
+/// the request span must declare it, e.g. with
+/// `tracing::info_span!("request", request_id = tracing::field::Empty)`;
+/// otherwise the request id is only a field of the error event.
+pub const REQUEST_ID_FIELD: &str = "request_id";
+
+macro_rules! error_event {
+    ($level:expr, $error:expr, $request_id:expr) => {{
+        let error: &ApiError = $error;
+        let class = error.class();
+        let err_path = DefaultCodeLayout::decode(error.code())
+            .ok()
+            .map(|v| format!("X{:02}/Y{:02}/Z{:02}", v.path[0], v.path[1], v.path[2]));
+        tracing::event!(
+            $level,
+            code = error.code(),
+            symbol = error.symbol(),
+            err_type = error.err_type().map(|v| format!("T{:04}", v.flag())),
+            err_path,
+            details = error.details().map(|v| format!("{v:?}")),
+            source = source_chain(error),
+            fault = class.map(|v| format!("{:?}", v.fault)),
+            retryable = class.map(|v| v.retryable),
+            request_id = $request_id,
+            "{}",
+            error.message()
+        )
+    }};
+}
+
+/// The messages of the source error and its own sources, joined with `: `.
+fn source_chain(error: &ApiError) -> Option<String> {
+    let mut source: Option<&(dyn Error + 'static)> = Some(error.source.as_deref()?);
+    let mut chain = String::new();
+    while let Some(current) = source {
+        if !chain.is_empty() {
+            chain.push_str(": ");
+        }
+        let _ = write!(chain, "{current}");
+        source = current.source();
+    }
+    Some(chain)
+}
+
+/// The level of the event of an error: the severity of its classification,
+/// or `WARN` when its code does not decode to an `ErrType`.
+fn level(error: &ApiError) -> Level {
+    match error.class().map(|v| v.severity) {
+        Some(Severity::Info) => Level::INFO,
+        Some(Severity::Warn) | None => Level::WARN,
+        Some(_) => Level::ERROR,
+    }
+}
+
+#[cfg(feature = "tokio")]
+fn context_request_id() -> Option<String> {
+    crate::meta_context::with_current_meta(|meta| meta.request_id().cloned()).flatten()
+}
+
+#[cfg(not(feature = "tokio"))]
+const fn context_request_id() -> Option<String> {
+    None
+}
+
+fn trace_error(error: &ApiError, request_id: Option<&str>) {
+    match level(error) {
+        Level::INFO => error_event!(Level::INFO, error, request_id),
+        Level::WARN => error_event!(Level::WARN, error, request_id),
+        _ => error_event!(Level::ERROR, error, request_id),
+    }
+}
+
+impl ApiError {
+    /// Emit a `tracing` event with the code, the decoded `ErrType` and
+    /// `ErrPath`, the message, the details and the source chain, at the level
+    /// of the error's [`class`](ApiError::class).
+    pub fn trace(&self) {
+        trace_error(self, None);
+    }
+}
+
+impl<Meta: ResponseMeta> ErrorResponse<Meta> {
+    /// Emit the event of the error, see [`ApiError::trace`], with the
+    /// [`request_id`](ResponseMeta::request_id) of the meta, or else of the
+    /// meta context, and record it in the [`REQUEST_ID_FIELD`] of the current
+    /// span if the span declared it.
+    ///
+    /// Responses rendered by the `salvo` and `axum` integrations are traced
+    /// automatically.
+    pub fn trace(&self) {
+        let request_id = self
+            .meta
+            .as_ref()
+            .and_then(ResponseMeta::request_id)
+            .map(ToOwned::to_owned)
+            .or_else(context_request_id);
+        if let Some(id) = &request_id {
+            Span::current().record(REQUEST_ID_FIELD, id.as_str());
+        }
+        trace_error(&self.error, request_id.as_deref());
+    }
+}
@@ -0,0 +1,289 @@
+//! TypeScript generation of the envelope types and the error code constants.
+//!
+//! The output only depends on its inputs, so it can be checked in and
+//! regenerated in CI.
+
+use std::fmt::Write;
+
+use crate::error_code::{catalog::Catalog, tally::ErrDeclTally};
+
+/// The shape of the generated `ApiResponse` union.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Envelope {
+    /// `{"status": "success" | "error", ...}`
+    Tagged,
+    /// `{"code": 0 | number, ...}`, the `lite` feature.
+    Lite,
+}
+
+impl Envelope {
+    /// The envelope this crate was built with.
+    pub const CONFIGURED: Self = if cfg!(feature = "lite") {
+        Self::Lite
+    } else {
+        Self::Tagged
+    };
+}
+
+const HEADER: &str = "// This file is generated by api-response. Do not edit it by hand.\n";
+
+const META: &str = r#"
+export interface UserMeta {
+  id: string;
+  roles: string[];
+}
+
+export interface Pagination {
+  currentPage: number;
+  pageSize: number;
+  totalPages: number;
+  totalRecords: number;
+  nextPage: number | null;
+  prevPage: number | null;
+}
+
+export interface CursorPagination {
+  nextCursor: string | null;
+  prevCursor: string | null;
+  hasMore: boolean;
+  estimatedTotal: number | null;
+}
+
+export interface RateLimit {
+  limit: number;
+  remaining: number;
+  restoreRate: number;
+  resetAt: string | null;
+}
+
+export interface Cost {
+  actualCost: number;
+  requestedQueryCost: number;
+  executionTime: string | null;
+}
+
+export interface DefaultMeta {
+  requestId?: string;
+  traceId?: string;
+  spanId?: string;
+  traceFlags?: number;
+  user?: UserMeta;
+  /** Set instead of `cursorPagination`. */
+  pagination?: Pagination;
+  /** Set instead of `pagination`. */
+  cursorPagination?: CursorPagination;
+  rateLimit?: RateLimit;
+  cost?: Cost;
+  apiVersion?: string;
+  locale?: string;
+  custom?: Record<string, string>;
+}
+"#;
+
+const TAGGED: &str = r#"
+export interface ApiError {
+  /** A string when the server is configured with symbolic codes. */
+  code: number | string;
+  message: string;
+  details?: Record<string, string>;
+}
+
+export interface SuccessResponse<T, M = DefaultMeta> {
+  status: "success";
+  data: T;
+  meta?: M;
+}
+
+export interface ErrorResponse<M = DefaultMeta> {
+  status: "error";
+  error: ApiError;
+  meta?: M;
+}
+
+export type ApiResponse<T, M = DefaultMeta> = SuccessResponse<T, M> | ErrorResponse<M>;
+
+export function isSuccess<T, M>(response: ApiResponse<T, M>): response is SuccessResponse<T, M> {
+  return response.status === "success";
+}
+"#;
+
+const LITE: &str = r#"
+export interface ApiError {
+  message: string;
+  details?: Record<string, string>;
+}
+
+export interface SuccessResponse<T, M = DefaultMeta> {
+  code: 0;
+  data: T;
+  meta?: M;
+}
+
+export interface ErrorResponse<M = DefaultMeta> {
+  /** A string when the server is configured with symbolic codes. */
+  code: number | string;
+  error: ApiError;
+  meta?: M;
+}
+
+export type ApiResponse<T, M = DefaultMeta> = SuccessResponse<T, M> | ErrorResponse<M>;
+
+export function isSuccess<T, M>(response: ApiResponse<T, M>): response is SuccessResponse<T, M> {
+  return response.code === 0;
+}
+"#;
+
+/// Generate a `.ts` module with the envelope types, the `DefaultMeta`
+/// interfaces and an `ERROR_CODES` constant of every code in the catalog.
+///
+/// When several texts share a code, the first one in catalog order is used.
+pub fn generate(catalog: &Catalog, envelope: Envelope) -> String {
+    let mut ts = String::from(HEADER);
+    ts.push_str(META);
+    ts.push_str(match envelope {
+        Envelope::Tagged => TAGGED,
+        Envelope::Lite => LITE,
+    });
+    ts.push_str("\nexport const ERROR_CODES = {\n");
+    let mut last_code = None;
+    for entry in &catalog.entries {
+        if last_code.replace(entry.code) == Some(entry.code) {
+            continue;
+        }
+        let _ = writeln!(
+            ts,
+            "  {}: {{ symbol: {}, message: {}, path: {}, httpStatus: {} }},",
+            entry.code,
+            string_literal(&entry.symbol),
+            string_literal(&entry.type_text),
+            string_literal(&entry.path_text()),
+            entry.http_status
+        );
+    }
+    ts.push_str("} as const;\n\nexport type ErrorCode = keyof typeof ERROR_CODES;\n");
+    ts
+}
+
+/// A double quoted string literal, escaped as JSON which is valid TypeScript.
+fn string_literal(text: &str) -> String {
+    serde_json::Value::from(text).to_string()
+}
+
+impl ErrDeclTally {
+    /// Generate TypeScript for the configured envelope, see [`generate`].
+    pub fn typescript(&self) -> String {
+        generate(&self.catalog(), Envelope::CONFIGURED)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use serde::Serialize;
+
+    use super::{Envelope, META, generate};
+    use crate::{
+        error_code::{ErrPath, ErrPathRoot, catalog::Catalog, ety_grpc},
+        meta::{Cost, CursorPagination, DefaultMeta, Pagination, RateLimit, UserMeta},
+    };
+
+    /// The fields of the `interface` in [`META`], sorted, each with whether it
+    /// is optional.
+    fn ts_fields(interface: &str) -> Vec<(String, bool)> {
+        let (_, body) = META.split_once(&format!("export interface {interface} {{\n")).unwrap();
+        let mut fields: Vec<(String, bool)> = body
+            .lines()
+            .take_while(|line| *line != "}")
+            .filter(|line| !line.trim_start().starts_with("/**"))
+            .map(|line| {
+                let (name, _) = line.trim().split_once(':').unwrap();
+                (name.trim_end_matches('?').to_owned(), name.ends_with('?'))
+            })
+            .collect();
+        fields.sort();
+        fields
+    }
+
+    /// The serialized keys of `value`, sorted.
+    fn json_keys(value: impl Serialize) -> Vec<String> {
+        let mut keys: Vec<String> = serde_json::to_value(value)
+            .unwrap()
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// The TypeScript meta types list the serialized fields of the Rust ones,
+    /// with `?` on exactly the fields that may be left out.
+    #[test]
+    fn meta_fields() {
+        fn required(interface: &str) -> Vec<String> {
+            ts_fields(interface)
+                .into_iter()
+                .filter(|(_, optional)| !optional)
+                .map(|(name, _)| name)
+                .collect()
+        }
+        fn all(interface: &str) -> Vec<String> {
+            ts_fields(interface).into_iter().map(|(name, _)| name).collect()
+        }
+        let mut full = DefaultMeta::new()
+            .with_request_id("req-1")
+            .with_trace_id("4bf92f3577b34da6a3ce929d0e0e4736")
+            .with_span_id("00f067aa0ba902b7")
+            .with_trace_flags(Some(1))
+            .with_user(Some(UserMeta::default()))
+            .with_rate_limit(Some(RateLimit::default()))
+            .with_cost(Some(Cost::default()))
+            .with_api_version("v1")
+            .with_locale("en")
+            .insert_custom("key", "value");
+        full.pagination = Some(Pagination::default());
+        full.cursor_pagination = Some(CursorPagination::default());
+        assert_eq!(all("DefaultMeta"), json_keys(&full));
+        assert_eq!(required("DefaultMeta"), json_keys(DefaultMeta::new()));
+
+        for (interface, keys) in [
+            ("UserMeta", json_keys(UserMeta::default())),
+            ("Pagination", json_keys(Pagination::default())),
+            ("CursorPagination", json_keys(CursorPagination::default())),
+            ("RateLimit", json_keys(RateLimit::default())),
+            ("Cost", json_keys(Cost::default())),
+        ] {
+            assert_eq!(all(interface), keys, "{interface}");
+            assert_eq!(required(interface), keys, "{interface}");
+        }
+    }
+
+    #[test]
+    fn deterministic_output() {
+        const EP: ErrPath = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        let err_decls = [
+            (ety_grpc::NOT_FOUND | "No \"user\".").declare(EP),
+            ety_grpc::INVALID_ARGUMENT.declare(EP),
+            ety_grpc::NOT_FOUND.declare(EP),
+        ];
+        let catalog = Catalog::from_err_decls(&err_decls);
+        let ts = generate(&catalog, Envelope::Tagged);
+        assert_eq!(
+            ts,
+            generate(&Catalog::from_err_decls(err_decls.iter().rev()), Envelope::Tagged)
+        );
+        assert!(ts.contains(r#"status: "success";"#));
+        assert!(ts.contains(
+            r#"  1002000120: { symbol: "PRODUCT_SYSTEM_USER_INVALID_ARGUMENT", message: "Invalid request argument.", path: "X00(product)/Y01(system)/Z20(user)", httpStatus: 400 },"#
+        ));
+        assert!(ts.contains(r#"  1004000120: { symbol: "PRODUCT_SYSTEM_USER_NOT_FOUND", message: "No \"user\".", "#));
+        assert_eq!(2, ts.matches("httpStatus: 4").count());
+        assert!(ts.ends_with("export type ErrorCode = keyof typeof ERROR_CODES;\n"));
+
+        let lite = generate(&catalog, Envelope::Lite);
+        assert!(lite.contains("  code: 0;\n"));
+        assert!(!lite.contains("status"));
+    }
+}
@@ -8,7 +8,7 @@ use std::{
 use serde::{Deserialize, Deserializer, Serialize, Serializer, ser::SerializeMap};
 
 #[cfg_attr(feature = "salvo", derive(salvo::prelude::ToSchema))]
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Default)]
 pub(crate) struct OrderedHashMap<K, V>(pub(crate) HashMap<K, V>);
 
 impl<K, V> Serialize for OrderedHashMap<K, V>
@@ -169,6 +169,7 @@ pub trait IntoError: Debug + Display + Sized {
 impl<E: Debug + Display + Sized> IntoError for E {}
 
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod tests {
     use std::{collections::HashMap, error::Error};
 
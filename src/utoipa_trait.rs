@@ -0,0 +1,169 @@
+use std::{borrow::Cow, collections::BTreeMap};
+
+use utoipa::{
+    IntoResponses, PartialSchema, ToSchema,
+    openapi::{
+        ContentBuilder, ObjectBuilder, OneOfBuilder, RefOr, ResponseBuilder, Schema, SchemaFormat, Type,
+        response::Response,
+    },
+};
+
+use crate::{ApiResponse, ErrCode};
+
+#[cfg(not(feature = "lite"))]
+fn success_schema<Data: PartialSchema, Meta: PartialSchema>() -> ObjectBuilder {
+    ObjectBuilder::new()
+        .property(
+            "status",
+            ObjectBuilder::new()
+                .schema_type(Type::String)
+                .enum_values(Some(["success"])),
+        )
+        .required("status")
+        .property("data", Data::schema())
+        .required("data")
+        .property("meta", Meta::schema())
+}
+
+#[cfg(not(feature = "lite"))]
+fn error_schema<Meta: PartialSchema>() -> ObjectBuilder {
+    ObjectBuilder::new()
+        .property(
+            "status",
+            ObjectBuilder::new()
+                .schema_type(Type::String)
+                .enum_values(Some(["error"])),
+        )
+        .required("status")
+        .property("error", crate::ApiError::schema())
+        .required("error")
+        .property("meta", Meta::schema())
+}
+
+#[cfg(feature = "lite")]
+fn success_schema<Data: PartialSchema, Meta: PartialSchema>() -> ObjectBuilder {
+    ObjectBuilder::new()
+        .property(
+            "code",
+            ObjectBuilder::new().schema_type(Type::Integer).enum_values(Some([0])),
+        )
+        .required("code")
+        .property("data", Data::schema())
+        .required("data")
+        .property("meta", Meta::schema())
+}
+
+#[cfg(feature = "lite")]
+fn error_schema<Meta: PartialSchema>() -> ObjectBuilder {
+    ObjectBuilder::new()
+        .property(
+            "code",
+            OneOfBuilder::new()
+                .item(ObjectBuilder::new().schema_type(Type::Integer).minimum(Some(1)))
+                .item(ObjectBuilder::new().schema_type(Type::String).min_length(Some(1))),
+        )
+        .required("code")
+        .property("error", crate::lite::__ApiError::schema())
+        .required("error")
+        .property("meta", Meta::schema())
+}
+
+/// A numeric or a symbolic code, see `CodeFormat`.
+impl PartialSchema for ErrCode {
+    fn schema() -> RefOr<Schema> {
+        OneOfBuilder::new()
+            .item(
+                ObjectBuilder::new()
+                    .schema_type(Type::Integer)
+                    // `KnownFormat::UInt64` needs `non_strict_integers` and
+                    // serializes as `u-int64`.
+                    .format(Some(SchemaFormat::Custom("uint64".to_owned())))
+                    .minimum(Some(0)),
+            )
+            .item(ObjectBuilder::new().schema_type(Type::String))
+            .into()
+    }
+}
+
+impl ToSchema for ErrCode {}
+
+impl<Data, Meta> PartialSchema for ApiResponse<Data, Meta>
+where
+    Data: ToSchema,
+    Meta: ToSchema,
+{
+    fn schema() -> RefOr<Schema> {
+        OneOfBuilder::new()
+            .item(success_schema::<Data, Meta>())
+            .item(error_schema::<Meta>())
+            .into()
+    }
+}
+
+impl<Data, Meta> ToSchema for ApiResponse<Data, Meta>
+where
+    Data: ToSchema,
+    Meta: ToSchema,
+{
+    fn name() -> Cow<'static, str> {
+        Cow::Owned(format!("ApiResponse_{}_{}", Data::name(), Meta::name()))
+    }
+    fn schemas(schemas: &mut Vec<(String, RefOr<Schema>)>) {
+        Data::schemas(schemas);
+        Meta::schemas(schemas);
+    }
+}
+
+/// A single `200` response: error envelopes are rendered with status 200 too,
+/// so the `oneOf` of the body tells a success from an error.
+impl<Data, Meta> IntoResponses for ApiResponse<Data, Meta>
+where
+    Data: ToSchema,
+    Meta: ToSchema,
+{
+    fn responses() -> BTreeMap<String, RefOr<Response>> {
+        let response = ResponseBuilder::new()
+            .description("Response with json format data")
+            .content(
+                "application/json",
+                ContentBuilder::new().schema(Some(Self::schema())).build(),
+            )
+            .build();
+        BTreeMap::from([("200".to_owned(), response.into())])
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::default_numeric_fallback)]
+mod tests {
+    use utoipa::{IntoResponses, PartialSchema};
+
+    use crate::{ApiResponse, DefaultMeta, ErrCode};
+
+    #[test]
+    fn one_of_schema() {
+        let schema = serde_json::to_value(ApiResponse::<String, DefaultMeta>::schema()).unwrap();
+        let one_of = schema["oneOf"].as_array().unwrap();
+        assert_eq!(2, one_of.len());
+        if cfg!(feature = "lite") {
+            assert_eq!(serde_json::json!([0]), one_of[0]["properties"]["code"]["enum"]);
+            assert_eq!(serde_json::json!(["code", "data"]), one_of[0]["required"]);
+            assert_eq!(serde_json::json!(["code", "error"]), one_of[1]["required"]);
+        } else {
+            assert_eq!(
+                serde_json::json!(["success"]),
+                one_of[0]["properties"]["status"]["enum"]
+            );
+            assert_eq!(serde_json::json!(["status", "data"]), one_of[0]["required"]);
+            assert_eq!(serde_json::json!(["error"]), one_of[1]["properties"]["status"]["enum"]);
+            assert_eq!(serde_json::json!(["status", "error"]), one_of[1]["required"]);
+        }
+        assert!(one_of[0]["properties"]["meta"]["properties"]["requestId"].is_object());
+
+        let responses = ApiResponse::<String, ()>::responses();
+        assert_eq!(vec!["200"], responses.keys().collect::<Vec<_>>());
+
+        let code = serde_json::to_value(ErrCode::schema()).unwrap();
+        assert_eq!("uint64", code["oneOf"][0]["format"]);
+    }
+}
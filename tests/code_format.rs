@@ -0,0 +1,36 @@
+use api_response::{
+    error_code::{ErrPathRoot, ety_grpc},
+    prelude::*,
+};
+
+#[test]
+fn symbolic_code_format() {
+    let err_decl = ety_grpc::NOT_FOUND.declare(ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("module"));
+    let response = ApiResponse::<(), ()>::from_error(err_decl.api_error());
+    let numeric = serde_json::to_string(&response).unwrap();
+
+    let response = response.with_code_format(CodeFormat::Symbolic);
+    let symbolic = serde_json::to_string(&response).unwrap();
+    let expected = if cfg!(feature = "lite") {
+        r#"{"code":"PRODUCT_SYSTEM_MODULE_NOT_FOUND","error":{"message":"Some requested entity was not found."}}"#
+    } else {
+        r#"{"status":"error","error":{"code":"PRODUCT_SYSTEM_MODULE_NOT_FOUND","message":"Some requested entity was not found."}}"#
+    };
+    assert_eq!(expected, symbolic);
+
+    // The format is per envelope.
+    let other = ApiResponse::<(), ()>::from_error(err_decl.api_error());
+    assert_eq!(numeric, serde_json::to_string(&other).unwrap());
+
+    // Errors without a symbol keep their numeric code.
+    let plain = ApiResponse::<(), ()>::from_error_msg(404u32, "not found").with_code_format(CodeFormat::Symbolic);
+    assert!(serde_json::to_string(&plain).unwrap().contains(r#""code":404"#));
+
+    // Both formats are accepted.
+    for json in [numeric, symbolic] {
+        let ApiResponse::Error(error_response) = serde_json::from_str::<ApiResponse<(), ()>>(&json).unwrap() else {
+            panic!("expected an error response");
+        };
+        assert!(error_response.error.code() == 1004000120 || error_response.error.symbol().is_some());
+    }
+}
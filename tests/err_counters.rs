@@ -0,0 +1,54 @@
+use api_response::{
+    api_err,
+    error_code::{
+        ErrPath, ErrPathRoot,
+        counter::{self, CountPoint},
+        ety_grpc,
+        tally::tally_err_decl,
+    },
+    prelude::*,
+};
+
+const USER: ErrPath = ErrPathRoot::x::<7>("shop").y::<1>("account").z::<1>("user");
+
+fn find_user(id: u32) -> Result<(), ApiError> {
+    if id == 0 {
+        return Err(api_err!(ety_grpc::INVALID_ARGUMENT, USER));
+    }
+    Err(api_err!(ety_grpc::NOT_FOUND, USER))
+}
+
+#[test]
+fn count_occurrences() {
+    let _ = find_user(1);
+    assert!(counter::snapshot().counts.is_empty(), "counting is opt-in");
+
+    counter::enable(CountPoint::Evaluate);
+    for id in [0, 1, 2, 3] {
+        let _ = find_user(id);
+    }
+    let not_found = ety_grpc::NOT_FOUND.declare(USER);
+    let invalid = ety_grpc::INVALID_ARGUMENT.declare(USER);
+    let internal = ety_grpc::INTERNAL.declare(USER);
+    counter::record_rendered(&internal.api_error());
+    let report = tally_err_decl().occurrences(&counter::snapshot());
+    assert_eq!(vec![(not_found, 3), (invalid, 1)], report.hot);
+    assert!(report.unseen.is_empty());
+
+    counter::reset();
+    counter::enable(CountPoint::Render);
+    let _ = find_user(1);
+    let response: ApiResponse<(), ()> = internal.api_error().into();
+    if let ApiResponse::Error(error_response) = &response {
+        counter::record_rendered(&error_response.error);
+    }
+    counter::record_rendered(&ApiError::new(404u32, "Not found."));
+    let report = tally_err_decl().occurrences(&counter::snapshot());
+    assert!(report.hot.is_empty());
+    assert_eq!(vec![invalid, not_found], report.unseen);
+    assert_eq!(vec![(404, 1), (internal.extract().code(), 1)], report.undeclared);
+
+    counter::disable();
+    counter::record_rendered(&internal.api_error());
+    assert_eq!(1, counter::snapshot().count(internal.extract().code()));
+}
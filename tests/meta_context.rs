@@ -0,0 +1,156 @@
+#![cfg(all(feature = "salvo", feature = "tokio"))]
+
+use std::sync::Arc;
+
+use api_response::{
+    i18n::{Localizer, MessageBundle},
+    prelude::*,
+};
+use salvo::{
+    prelude::*,
+    test::{ResponseExt, TestClient},
+};
+use serde::Serialize;
+use serde_json::{Value, json};
+
+#[derive(Serialize)]
+struct TenantMeta {
+    tenant: &'static str,
+}
+
+impl ResponseMeta for TenantMeta {}
+
+#[handler]
+async fn inject_meta(depot: &mut Depot) {
+    depot.insert_typed(
+        DefaultMeta::new()
+            .with_request_id("req-7")
+            .with_api_version("v1")
+            .insert_custom("region", "eu")
+            .insert_custom("tenant", "context"),
+    );
+}
+
+#[handler]
+async fn get_user() -> ApiResponse<Value, DefaultMeta> {
+    assert_eq!(Some("req-7"), current_meta().unwrap().request_id().map(String::as_str));
+    json!({"name": "Andeya"}).api_response_with_meta(
+        DefaultMeta::new()
+            .with_api_version("v2")
+            .insert_custom("tenant", "handler"),
+    )
+}
+
+#[handler]
+async fn get_error() -> ApiResponse<Value, DefaultMeta> {
+    ApiError::new(1404u32, "Not found.").api_response_without_meta()
+}
+
+#[handler]
+async fn get_tenant() -> ApiResponse<Value, TenantMeta> {
+    Value::Null.api_response_with_meta(TenantMeta { tenant: "handler" })
+}
+
+#[tokio::test]
+async fn salvo_meta_headers() {
+    let mapping = HeaderMapping::default().with_request_id(MetaPlacement::Header);
+    let router = Router::new()
+        .hoop(inject_meta)
+        .hoop(MetaHeaders::new(mapping))
+        .hoop(MetaContext)
+        .push(Router::with_path("error").get(get_error));
+    let service = Service::new(router);
+
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let mut res = TestClient::get("http://127.0.0.1/error")
+        .add_header("x-request-id", "req-9", true)
+        .add_header("traceparent", traceparent, true)
+        .send(&service)
+        .await;
+    assert_eq!("req-7", res.headers()["x-request-id"]);
+    assert_eq!("v1", res.headers()["api-version"]);
+    assert!(res.headers().contains_key("traceparent"));
+    let body: Value = res.take_json().await.unwrap();
+    assert!(body["meta"].get("requestId").is_none());
+    assert_eq!("4bf92f3577b34da6a3ce929d0e0e4736", body["meta"]["traceId"]);
+}
+
+#[tokio::test]
+async fn salvo_meta_context() {
+    let router = Router::new()
+        .hoop(inject_meta)
+        .hoop(MetaContext)
+        .push(Router::with_path("user").get(get_user))
+        .push(Router::with_path("tenant").get(get_tenant))
+        .push(Router::with_path("error").get(get_error));
+    let service = Service::new(router);
+
+    let body: Value = TestClient::get("http://127.0.0.1/user")
+        .send(&service)
+        .await
+        .take_json()
+        .await
+        .unwrap();
+    assert_eq!(
+        json!({"requestId": "req-7", "apiVersion": "v2", "custom": {"region": "eu", "tenant": "handler"}}),
+        body["meta"]
+    );
+
+    let body: Value = TestClient::get("http://127.0.0.1/error")
+        .send(&service)
+        .await
+        .take_json()
+        .await
+        .unwrap();
+    assert_eq!(
+        json!({"requestId": "req-7", "apiVersion": "v1", "custom": {"region": "eu", "tenant": "context"}}),
+        body["meta"]
+    );
+
+    let body: Value = TestClient::get("http://127.0.0.1/tenant")
+        .send(&service)
+        .await
+        .take_json()
+        .await
+        .unwrap();
+    assert_eq!(json!({"tenant": "handler"}), body["meta"]);
+
+    assert!(current_meta().is_none());
+    let meta = scope_meta(DefaultMeta::new().with_request_id("req-8"), async {
+        tokio::task::yield_now().await;
+        current_meta()
+    })
+    .await;
+    assert_eq!(Some("req-8"), meta.unwrap().request_id().map(String::as_str));
+}
+
+#[tokio::test]
+async fn salvo_localize() {
+    let localizer = Localizer::new()
+        .with_bundle(MessageBundle::new("zh").with_code(1404u32, "未找到。"))
+        .with_bundle(MessageBundle::new("fr").with_code(1404u32, "Introuvable."));
+    let router = Router::new()
+        .hoop(inject_meta)
+        .hoop(MetaContext)
+        .hoop(Localize::new(Arc::new(localizer)))
+        .push(Router::with_path("error").get(get_error));
+    let service = Service::new(router);
+
+    let body: Value = TestClient::get("http://127.0.0.1/error")
+        .add_header("accept-language", "fr-CA, zh;q=0.5", true)
+        .send(&service)
+        .await
+        .take_json()
+        .await
+        .unwrap();
+    assert_eq!("Introuvable.", body["error"]["message"]);
+    assert_eq!("fr", body["meta"]["locale"]);
+
+    let body: Value = TestClient::get("http://127.0.0.1/error")
+        .send(&service)
+        .await
+        .take_json()
+        .await
+        .unwrap();
+    assert_eq!("Not found.", body["error"]["message"]);
+}
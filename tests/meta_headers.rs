@@ -0,0 +1,53 @@
+#![cfg(all(feature = "axum", feature = "tokio"))]
+
+use std::sync::Arc;
+
+use api_response::{
+    i18n::{Localizer, MessageBundle},
+    prelude::*,
+};
+use axum::response::IntoResponse;
+use serde_json::Value;
+
+#[tokio::test]
+async fn axum_meta_headers() {
+    let mapping = HeaderMapping::default().with_api_version(MetaPlacement::Header);
+    let context = DefaultMeta::new().with_request_id("req-1");
+    let response = scope_header_mapping(mapping, async {
+        scope_meta(context, async {
+            let meta = DefaultMeta::new().with_api_version("v2");
+            Value::Null.api_response_with_meta(meta).into_response()
+        })
+        .await
+    })
+    .await;
+    assert_eq!("req-1", response.headers()["x-request-id"]);
+    assert_eq!("v2", response.headers()["api-version"]);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let value: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(serde_json::json!({"requestId": "req-1"}), value["meta"]);
+
+    let response = ApiError::new(1404u32, "Not found.")
+        .api_response_with_meta::<Value, _>(DefaultMeta::new().with_request_id("req-2"))
+        .into_response();
+    assert!(!response.headers().contains_key("x-request-id"));
+}
+
+#[tokio::test]
+async fn axum_localize() {
+    let localizer = Arc::new(Localizer::new().with_bundle(MessageBundle::new("zh").with_code(1404u32, "未找到。")));
+    let context = DefaultMeta::new().with_locale("zh-CN");
+    let response = scope_localizer(localizer, Some("fr".to_owned()), async {
+        scope_meta(context, async {
+            ApiError::new(1404u32, "Not found.")
+                .api_response_without_meta::<Value, DefaultMeta>()
+                .into_response()
+        })
+        .await
+    })
+    .await;
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let value: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!("未找到。", value["error"]["message"]);
+    assert_eq!("zh", value["meta"]["locale"]);
+}
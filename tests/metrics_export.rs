@@ -0,0 +1,78 @@
+#![cfg(feature = "metrics")]
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use api_response::{
+    api_err,
+    error_code::{
+        ErrPathRoot,
+        counter::{self, CountPoint},
+        ety_grpc,
+        tally::tally_err_decl,
+    },
+};
+use metrics::{
+    Counter, CounterFn, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit, with_local_recorder,
+};
+
+struct AbsoluteCounter(AtomicU64);
+
+impl CounterFn for AbsoluteCounter {
+    fn increment(&self, value: u64) {
+        self.0.fetch_add(value, Ordering::Relaxed);
+    }
+    fn absolute(&self, value: u64) {
+        self.0.fetch_max(value, Ordering::Relaxed);
+    }
+}
+
+/// The counters by their sorted `name{label=value,...}`.
+#[derive(Default)]
+struct TestRecorder(Mutex<HashMap<String, Arc<AbsoluteCounter>>>);
+
+impl Recorder for TestRecorder {
+    fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+    fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+        let mut labels: Vec<String> = key.labels().map(|v| format!("{}={}", v.key(), v.value())).collect();
+        labels.sort();
+        let name = format!("{}{{{}}}", key.name(), labels.join(","));
+        let counter = self
+            .0
+            .lock()
+            .unwrap()
+            .entry(name)
+            .or_insert_with(|| Arc::new(AbsoluteCounter(AtomicU64::new(0))))
+            .clone();
+        Counter::from_arc(counter)
+    }
+    fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+        Gauge::noop()
+    }
+    fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+        Histogram::noop()
+    }
+}
+
+#[test]
+fn export_metrics() {
+    counter::enable(CountPoint::Evaluate);
+    for _ in 0..2 {
+        let _ = api_err!(
+            ety_grpc::NOT_FOUND,
+            ErrPathRoot::x::<7>("shop").y::<1>("account").z::<1>("user")
+        );
+    }
+    let recorder = TestRecorder::default();
+    with_local_recorder(&recorder, || counter::export_metrics(&tally_err_decl()));
+    let counters = recorder.0.lock().unwrap();
+    let counter = &counters["api_response_errors_total{code=1004070101,type=T1004,x=shop,y=account,z=user}"];
+    assert_eq!(2, counter.0.load(Ordering::Relaxed));
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "reqwest")]
+
+use std::time::Duration;
+
+use api_response::{error_code::ErrPath, prelude::*};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+};
+
+/// Serve a single canned HTTP response and return the base url.
+async fn mock_server(status: &'static str, body: &'static str) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = [0u8; 1024];
+        let _ = socket.read(&mut buf).await.unwrap();
+        let response = format!(
+            "HTTP/1.1 {status}\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+            body.len()
+        );
+        socket.write_all(response.as_bytes()).await.unwrap();
+    });
+    format!("http://{addr}")
+}
+
+fn code_of(err_type: error_code::ErrType) -> u64 {
+    err_type.extract(&ErrPath::default()).code()
+}
+
+#[tokio::test]
+async fn decode_success() {
+    let body = if cfg!(feature = "lite") {
+        r#"{"code":0,"data":"hello","meta":{"requestId":"abc-123"}}"#
+    } else {
+        r#"{"status":"success","data":"hello","meta":{"requestId":"abc-123"}}"#
+    };
+    let url = mock_server("200 OK", body).await;
+    let resp: ApiResponse<String, DefaultMeta> = reqwest::get(url).await.decode_api_response().await;
+    let success = resp.unwrap();
+    assert_eq!("hello", success.data);
+    assert_eq!(Some(&"abc-123".to_owned()), success.meta.unwrap().request_id());
+}
+
+#[tokio::test]
+async fn decode_error_envelope() {
+    let body = if cfg!(feature = "lite") {
+        r#"{"code":1004000120,"error":{"message":"user not found"}}"#
+    } else {
+        r#"{"status":"error","error":{"code":1004000120,"message":"user not found"}}"#
+    };
+    let url = mock_server("404 Not Found", body).await;
+    let resp: ApiResponse<String, ()> = reqwest::get(url).await.decode_api_response().await;
+    let error = resp.unwrap_err();
+    assert_eq!(1004000120, error.code());
+    assert_eq!("user not found", error.message());
+    assert_eq!(Some(&"404".to_owned()), error.detail(HTTP_STATUS_DETAIL));
+}
+
+#[tokio::test]
+async fn decode_success_envelope_with_error_status() {
+    let body = if cfg!(feature = "lite") {
+        r#"{"code":0,"data":"hello"}"#
+    } else {
+        r#"{"status":"success","data":"hello"}"#
+    };
+    let url = mock_server("500 Internal Server Error", body).await;
+    let resp: ApiResponse<String, ()> = reqwest::get(url).await.decode_api_response().await;
+    let error = resp.unwrap_err();
+    assert_eq!(code_of(ety_grpc::INTERNAL), error.code());
+    assert_eq!("Internal Server Error", error.message());
+    assert_eq!(Some(&"500".to_owned()), error.detail(HTTP_STATUS_DETAIL));
+    assert_eq!(Some(&body.to_owned()), error.detail(HTTP_BODY_DETAIL));
+}
+
+#[tokio::test]
+async fn decode_empty_body() {
+    let url = mock_server("204 No Content", "").await;
+    let resp: ApiResponse<(), ()> = reqwest::get(url).await.decode_api_response().await;
+    assert!(resp.is_success());
+
+    let url = mock_server("503 Service Unavailable", "").await;
+    let resp: ApiResponse<(), ()> = reqwest::get(url).await.decode_api_response().await;
+    let error = resp.unwrap_err();
+    assert_eq!(code_of(ety_grpc::UNAVAILABLE), error.code());
+    assert_eq!(Some(&"503".to_owned()), error.detail(HTTP_STATUS_DETAIL));
+}
+
+#[tokio::test]
+async fn decode_non_envelope_body() {
+    let url = mock_server("502 Bad Gateway", "<html>bad gateway</html>").await;
+    let resp: ApiResponse<String, ()> = reqwest::get(url).await.decode_api_response().await;
+    let error = resp.unwrap_err();
+    assert_eq!(code_of(ety_grpc::UNAVAILABLE), error.code());
+    assert_eq!("Bad Gateway", error.message());
+    assert_eq!(
+        Some(&"<html>bad gateway</html>".to_owned()),
+        error.detail(HTTP_BODY_DETAIL)
+    );
+    assert!(error.is::<serde_json::Error>());
+}
+
+#[tokio::test]
+async fn transport_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    let resp: ApiResponse<String, ()> = reqwest::get(format!("http://{addr}")).await.decode_api_response().await;
+    assert_eq!(code_of(ety_grpc::UNAVAILABLE), resp.unwrap_err().code());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (_socket, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(5)).await;
+    });
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let resp: ApiResponse<String, ()> = client
+        .get(format!("http://{addr}"))
+        .send()
+        .await
+        .decode_api_response()
+        .await;
+    assert_eq!(code_of(ety_grpc::DEADLINE_EXCEEDED), resp.unwrap_err().code());
+}
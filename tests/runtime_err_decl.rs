@@ -0,0 +1,29 @@
+use api_response::error_code::{
+    ErrPathRoot, ErrType,
+    catalog::Catalog,
+    ety_grpc,
+    tally::{register_err_decls, tally_err_decl},
+};
+
+#[test]
+fn register_loaded_catalog() {
+    let name = String::from("billing");
+    let err_path = ErrPathRoot::try_from(3, name.clone())
+        .and_then(|v| v.try_to_child(1, format!("{name}-api")))
+        .and_then(|v| v.try_to_child(2, String::from("invoice")))
+        .unwrap();
+    let err_type = ErrType::try_new(1004, String::from("Invoice not found.")).unwrap();
+    let json =
+        Catalog::from_err_decls(&[err_type.declare(err_path), ety_grpc::UNAVAILABLE.declare(err_path)]).to_json();
+
+    let catalog = Catalog::from_json(&json).unwrap();
+    register_err_decls(catalog.err_decls().unwrap());
+
+    let tally = tally_err_decl();
+    assert_eq!(2, tally.unique().len());
+    assert_eq!(catalog, tally.catalog());
+    assert!(tally.json().contains("Y01(billing-api)"));
+    let api_error = tally.unique()[0].api_error();
+    assert_eq!(1004030102, api_error.code());
+    assert_eq!("Invoice not found.", api_error.message());
+}
@@ -0,0 +1,21 @@
+//! Runtime status mappings are process-wide, so they are registered in their
+//! own test binary.
+
+use api_response::error_code::{
+    ErrType, ety_grpc,
+    status_map::{StatusMapping, register_status_mapping},
+};
+use http::StatusCode;
+
+#[test]
+fn overrides() {
+    const LOCKED: ErrType = ErrType::t::<4001>("Locked.");
+    register_status_mapping(StatusMapping::new(LOCKED, 423));
+    assert_eq!(StatusCode::LOCKED, StatusCode::from(LOCKED));
+    assert_eq!(LOCKED, ErrType::from(StatusCode::LOCKED));
+
+    const GONE: ErrType = ErrType::t::<4002>("Gone.");
+    register_status_mapping(StatusMapping::to_status(GONE, 410));
+    assert_eq!(StatusCode::GONE, StatusCode::from(GONE));
+    assert_eq!(ety_grpc::DATA_LOSS, ErrType::from(StatusCode::GONE));
+}
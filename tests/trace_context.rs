@@ -0,0 +1,40 @@
+#![cfg(feature = "opentelemetry")]
+
+use api_response::prelude::*;
+use opentelemetry::{
+    Context,
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+};
+
+#[test]
+fn current_trace() {
+    assert_eq!(None, TraceParent::current());
+    assert!(DefaultMeta::new().with_current_trace().trace_id().is_none());
+
+    let span_context = SpanContext::new(
+        TraceId::from_bytes(0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736_u128.to_be_bytes()),
+        SpanId::from_bytes(0x00f0_67aa_0ba9_02b7_u64.to_be_bytes()),
+        TraceFlags::SAMPLED,
+        true,
+        TraceState::default(),
+    );
+    let _guard = Context::new().with_remote_span_context(span_context).attach();
+    let parent = TraceParent::current().unwrap();
+    assert_eq!(
+        "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        parent.to_string()
+    );
+    let meta = DefaultMeta::new().with_current_trace();
+    assert_eq!(
+        Some("4bf92f3577b34da6a3ce929d0e0e4736"),
+        meta.trace_id().map(String::as_str)
+    );
+    assert_eq!(Some(parent), meta.trace_parent());
+    let meta = DefaultMeta::new()
+        .with_trace_id("0af7651916cd43dd8448eb211c80319c")
+        .with_current_trace();
+    assert_eq!(
+        Some("0af7651916cd43dd8448eb211c80319c"),
+        meta.trace_id().map(String::as_str)
+    );
+}
@@ -0,0 +1,125 @@
+#![cfg(feature = "tracing")]
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use api_response::{
+    error_code::{ErrPathRoot, ety_grpc},
+    prelude::*,
+};
+use tracing::{
+    Event, Level, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+};
+use tracing_core::span::Current;
+
+/// The level and fields of the recorded events, and the fields recorded on
+/// spans.
+#[derive(Default)]
+struct Recorded {
+    events: Vec<(Level, HashMap<String, String>)>,
+    span: Option<&'static Metadata<'static>>,
+    entered: bool,
+    span_fields: HashMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Recorded>>);
+
+struct FieldVisitor<'a>(&'a mut HashMap<String, String>);
+
+impl Visit for FieldVisitor<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_owned(), format!("{value:?}"));
+    }
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.to_owned());
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        self.0.lock().unwrap().span = Some(span.metadata());
+        Id::from_u64(1)
+    }
+    fn record(&self, _span: &Id, values: &Record<'_>) {
+        values.record(&mut FieldVisitor(&mut self.0.lock().unwrap().span_fields));
+    }
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = HashMap::new();
+        event.record(&mut FieldVisitor(&mut fields));
+        self.0.lock().unwrap().events.push((*event.metadata().level(), fields));
+    }
+    fn enter(&self, _span: &Id) {
+        self.0.lock().unwrap().entered = true;
+    }
+    fn exit(&self, _span: &Id) {
+        self.0.lock().unwrap().entered = false;
+    }
+    fn current_span(&self) -> Current {
+        let recorded = self.0.lock().unwrap();
+        match recorded.span {
+            Some(metadata) if recorded.entered => Current::new(Id::from_u64(1), metadata),
+            _ => Current::none(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Outer(std::num::ParseIntError);
+
+impl fmt::Display for Outer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid id")
+    }
+}
+
+impl std::error::Error for Outer {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+#[test]
+fn error_events() {
+    let recorder = Recorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        let span = tracing::info_span!("request", request_id = tracing::field::Empty);
+        let _guard = span.enter();
+        let ep = ErrPathRoot::x::<0>("product").y::<1>("system").z::<20>("user");
+        ErrorResponse::new(
+            ety_grpc::UNAVAILABLE
+                .declare(ep)
+                .api_error()
+                .with_detail("key", "value")
+                .with_source(Outer("@".parse::<u8>().unwrap_err()), false),
+            DefaultMeta::new().with_request_id("abc-123"),
+        )
+        .trace();
+        ety_grpc::NOT_FOUND.declare(ep).api_error().trace();
+    });
+
+    let recorded = recorder.0.lock().unwrap();
+    assert_eq!(Some(&"abc-123".to_owned()), recorded.span_fields.get("request_id"));
+    let (level, fields) = &recorded.events[0];
+    assert_eq!(Level::ERROR, *level);
+    assert_eq!("1013000120", fields["code"]);
+    assert_eq!("T1013", fields["err_type"]);
+    assert_eq!("X00/Y01/Z20", fields["err_path"]);
+    assert_eq!("invalid id: invalid digit found in string", fields["source"]);
+    assert_eq!("abc-123", fields["request_id"]);
+    assert_eq!("true", fields["retryable"]);
+    assert_eq!(ety_grpc::UNAVAILABLE.text(), fields["message"]);
+
+    let (level, fields) = &recorded.events[1];
+    assert_eq!(Level::INFO, *level);
+    assert!(!fields.contains_key("request_id"));
+}